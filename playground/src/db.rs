@@ -1,13 +1,8 @@
 
-use std::io::Write;
-use std::ops::Deref;
 use std::path::Path;
 
 
-use versebase::table::{Table};
-use versebase::index::{TableIndex};
-use versebase::datatypes::{Int, Str, DateTime, DataType};
-use versebase::datatypes;
+use versebase::table::{HasTable, Table};
 
 use super::schemas::{Songs, Lyrics, Artists, LikedSongs, Users};
 
@@ -20,54 +15,34 @@ pub struct Database {
     pub liked_songs: Table<LikedSongs>,
 }
 
+impl Default for Database {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Database {
     pub fn new() -> Self {
-        let users = Table::<Users>::new(
-            String::from("users"),
-            Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/users.tbl")),
-            Some(TableIndex::new(
-                Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/users.idx"))
-            ).unwrap()),
-        ).unwrap();
-
-        let songs = Table::<Songs>::new(
-            String::from("songs"),
-            Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/songs.tbl")),
-            Some(TableIndex::new(
-                Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/songs.idx"))
-            ).unwrap()),
-        ).unwrap();
-
-        let lyrics = Table::<Lyrics>::new(
-            String::from("lyrics"),
-            Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/lyrics.tbl")),
-            Some(TableIndex::new(
-                Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/lyrics.idx"))
-            ).unwrap()),
-        ).unwrap();
-
-        let artists = Table::<Artists>::new(
-            String::from("artists"),
-            Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/artists.tbl")),
-            Some(TableIndex::new(
-                Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/artists.idx"))
-            ).unwrap()),
-        ).unwrap();
-
-        let liked_songs = Table::<LikedSongs>::new(
-            String::from("liked_songs"),
-            Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/liked_songs.tbl")),
-            Some(TableIndex::new(
-                Box::from(Path::new("/home/a/CLionProjects/versebase_playground/data/liked_songs.idx"))
-            ).unwrap()),
-        ).unwrap();
+        let dir = Path::new("/home/a/CLionProjects/versebase_playground/data");
 
         Self {
-            users,
-            songs,
-            lyrics,
-            artists,
-            liked_songs,
+            users: Table::open(dir).unwrap(),
+            songs: Table::open(dir).unwrap(),
+            lyrics: Table::open(dir).unwrap(),
+            artists: Table::open(dir).unwrap(),
+            liked_songs: Table::open(dir).unwrap(),
         }
     }
 }
+
+macro_rules! has_table {
+    ($($field:ident: $schema:ty),*) => {$(
+        impl HasTable<$schema> for Database {
+            fn table(&mut self) -> &mut Table<$schema> {
+                &mut self.$field
+            }
+        }
+    )*};
+}
+
+has_table!(users: Users, songs: Songs, lyrics: Lyrics, artists: Artists, liked_songs: LikedSongs);
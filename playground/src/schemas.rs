@@ -1,81 +1,66 @@
-use std::any::Any;
-use std::io::Write;
-use std::ops::Deref;
-use std::path::Path;
+use versebase::datatypes::{DataType, Int, Str, VarChar, DateTime, Timestamp, Bytes};
 
 
-use versebase::error;
-use versebase::table::{Table, TableSchema};
-use versebase::index::{TableIndex};
-use versebase::datatypes::{Int, Str, DateTime, DataType, DType};
-use versebase::datatypes;
-use super::db::Database;
-
+#[derive(VersebaseEnum, Debug, Clone, PartialEq)]
+pub enum Language {
+    En,
+    Uk,
+    De,
+    Fr,
+    Es,
+    Pl,
+}
 
 #[derive(TableSchema, Debug)]
 pub struct Users {
     pub id: Int,
+    #[versebase(unique, validate = is_email)]
     pub email: Str,
     pub password: Str,
-    pub salt: Str,
-    pub language: Str,
+    pub salt: Bytes,
+    pub language: Language,
+    #[versebase(auto_now_add)]
     pub last_login: DateTime,
 }
 
+fn is_email(email: &Str) -> Result<(), &'static str> {
+    match email.get().split_once('@') {
+        Some((user, domain)) if !user.is_empty() && domain.contains('.') => Ok(()),
+        _ => Err("must be an email address"),
+    }
+}
+
 #[derive(TableSchema, Debug)]
 pub struct Artists {
     pub id: Int,
-    pub name: Str,
-}
-
-impl Artists {
-    pub fn get_songs(&self, db: &mut Database) -> Vec<Songs> {
-        db.songs.get(1);
-        vec![]
-    }
+    pub name: VarChar<200>,
 }
 
 #[derive(TableSchema, Debug)]
 pub struct Songs {
     pub id: Int,
     pub name: Str,
+    #[versebase(references = Artists)]
     pub artist_id: Int,
 }
 
-impl Songs {
-    pub fn get_artist(&self, db: &mut Database) -> Result<Artists, versebase::error::Error> {
-        db.artists.get(self.artist_id.get())
-    }
-}
-
 
 #[derive(TableSchema, Debug)]
 pub struct Lyrics {
     pub id: Int,
     pub text: Str,
-    pub language: Str,
+    pub language: Language,
+    #[versebase(references = Songs)]
     pub song_id: Int,
 }
 
-impl Lyrics {
-    pub fn get_song(&self, db: &mut Database) -> Result<Songs, versebase::error::Error> {
-        db.songs.get(self.song_id.get())
-    }
-}
-
 #[derive(TableSchema, Debug)]
 pub struct LikedSongs {
     pub id: Int,
+    #[versebase(references = Songs)]
     pub song_id: Int,
+    #[versebase(references = Users)]
     pub user_id: Int,
-    pub created_at: DateTime,
-}
-
-impl LikedSongs {
-    pub fn get_user(&self, db: &mut Database) -> Result<Users, versebase::error::Error> {
-        db.users.get(self.user_id.get())
-    }
-    pub fn get_song(&self, db: &mut Database) -> Result<Songs, versebase::error::Error> {
-        db.songs.get(self.song_id.get())
-    }
+    #[versebase(auto_now_add)]
+    pub created_at: Timestamp,
 }
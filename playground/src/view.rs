@@ -1,11 +1,9 @@
 use std::io::{self, BufRead, Write};
 use regex::Regex;
+use versebase::query::{Order, QueryBuilder};
 use versebase::table::TableSchema;
 use super::db::{Database};
 use super::schemas::*;
-use versebase::datatypes::{Int, Str, DateTime, DataType, DType};
-use std::collections::HashMap;
-use versebase::datatypes;
 
 
 pub struct Playground {
@@ -20,6 +18,12 @@ struct Command {
 }
 
 
+impl Default for Playground {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Playground {
     pub fn new() -> Self {
         Self {
@@ -44,6 +48,7 @@ impl Playground {
                     "update" => self.update(command),
                     "insert" => self.insert(command),
                     "delete" => self.delete(command),
+                    "describe" => self.describe(command),
                     "help" => self.help(),
                     "exit" => break,
                     _ => self.help(),
@@ -58,17 +63,18 @@ impl Playground {
 
     fn parse_db_operation(&self, input: &str) -> Option<Command> {
         let command_name_re = Regex::new(r#"^\s*?(\w+)\s*?.*?$"#).unwrap();
-        let argument_re = Regex::new(r#"\s*?([\w_]+)\s*?"#).unwrap();
+        // Either a double-quoted string or a run of non-whitespace characters
+        let argument_re = Regex::new(r#""([^"]*)"|(\S+)"#).unwrap();
 
         let command_name_cap = command_name_re
-            .captures(&input)?
+            .captures(input)?
             .get(1)?;
 
         let command_name = command_name_cap.as_str().to_string();
 
         let arguments: Vec<String> = argument_re
             .captures_iter(&input[command_name_cap.end()..])
-            .map(|m| m.get(1).unwrap().as_str().to_string())
+            .map(|m| m.get(1).or_else(|| m.get(2)).unwrap().as_str().to_string())
             .collect()
             ;
 
@@ -81,8 +87,8 @@ impl Playground {
             return;
         }
 
-        match (&command.arguments[0]).as_str() {
-            "artists" => match &self.db.artists.select([].into()) {
+        match command.arguments[0].as_str() {
+            "artists" => match &self.db.artists.execute(Artists::query().order_by(ArtistsColumn::Name, Order::Asc)) {
                 Ok(artists) => {
                     for artist in artists {
                         println!("{}", artist);
@@ -90,7 +96,7 @@ impl Playground {
                 },
                 Err(e) => println!("Error: {}", e.message)
             },
-            "songs" => match &self.db.songs.select([].into()) {
+            "songs" => match &self.db.songs.execute(Songs::query().order_by(SongsColumn::Name, Order::Asc)) {
                 Ok(songs) => {
                     for song in songs {
                         println!("{}", song);
@@ -100,7 +106,6 @@ impl Playground {
             },
             table_name => {
                 println!("Table \"{}\" is not supported", table_name);
-                return;
             }
         }
     }
@@ -114,13 +119,13 @@ impl Playground {
         let id = match command.arguments[1].parse::<i32>() {
             Ok(val) => val,
             Err(_) => {
-                println!("{} is not a valid id!", &command.arguments[1]);
+                println!("{} is not a valid id!", command.arguments[1]);
                 return;
             }
         };
 
         // let record: Option<dyn TableSchema>;
-        match (&command.arguments[0]).as_str() {
+        match command.arguments[0].as_str() {
             "artists" => match &self.db.artists.get(id) {
                 Ok(artist) => println!("{}", artist),
                 Err(e) => println!("Error: {}", e.message)
@@ -131,173 +136,132 @@ impl Playground {
             },
             table_name => {
                 println!("Table \"{}\" is not supported", table_name);
-                return;
             }
         }
     }
 
     fn update(&mut self, command: Command) {
         if command.arguments.len() < 3 {
-            println!("Usage: insert [artists, songs] [field1>; <field2>; ...]");
+            println!("Usage: update [artists, songs] <id> [<field2> <field3> ...]");
             return;
         }
-        let table = (&command.arguments[0]).to_string();
+        let table = command.arguments[0].to_string();
 
         match table.as_str() {
             "artists" => {
-                if command.arguments.len() != 3 {
-                    println!("artists table consists of exactly 2 fields. Check your input and try again.");
-                    return;
-                }
-                let id = match command.arguments[1].parse::<i32>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        println!("{} is not a valid id!", &command.arguments[1]);
-                        return;
-                    }
+                let artist = match Self::parse_row::<Artists>(&command.arguments[1..]) {
+                    Ok(artist) => artist,
+                    Err(message) => {println!("{}", message); return}
                 };
-                let name = command.arguments[2].clone();
-
-                if let Err(e) = self.db.artists.delete(id.clone()) {
-                    println!("Error: {}", e.message);
-                    return;
-                }
-
-                let artist = Artists {
-                    id: Int::new(id),
-                    name: Str::new(name),
-                };
-                match self.db.artists.create(artist) {
-                    Ok(_id) => println!("Updated artist with id = {}", _id),
+                let id = artist.get_id();
+                match self.db.artists.update(artist) {
+                    Ok(()) => println!("Updated artist with id = {}", id),
                     Err(e) => println!("Error: {}", e.message)
                 }
             },
             "songs" => {
-                if command.arguments.len() != 4 {
-                    println!("songs table consists of exactly 3 fields. Check your input and try again.");
-                    return;
-                }
-                let id = match command.arguments[1].parse::<i32>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        println!("{} is not a valid id!", &command.arguments[1]);
-                        return;
-                    }
-                };
-                let name = command.arguments[2].clone();
-                let artist_id = match command.arguments[3].parse::<i32>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        println!("{} is not a valid artist foreign key!", &command.arguments[1]);
-                        return;
-                    }
+                let song = match Self::parse_row::<Songs>(&command.arguments[1..]) {
+                    Ok(song) => song,
+                    Err(message) => {println!("{}", message); return}
                 };
 
                 // Check if given artist exists
-                match self.db.artists.get(artist_id.clone()) {
+                match song.artist(&mut self.db) {
                     Ok(_) => {},
                     Err(e) => {println!("Error: {}", e.message); return}
                 }
 
-
-                if let Err(e) = self.db.songs.delete(id.clone()) {
-                    println!("Error: {}", e.message);
-                    return;
-                }
-
-
-                let song = Songs {
-                    id: Int::new(id),
-                    name: Str::new(name),
-                    artist_id: Int::new(artist_id)
-                };
-                match self.db.songs.create(song) {
-                    Ok(_id) => println!("Updated song with id = {}", _id),
+                let id = song.get_id();
+                match self.db.songs.update(song) {
+                    Ok(()) => println!("Updated song with id = {}", id),
                     Err(e) => println!("Error: {}", e.message)
                 }
             },
             table_name => {
                 println!("Table \"{}\" is not supported", table_name);
-                return;
             }
         }
     }
 
     fn insert(&mut self, command: Command) {
-        if command.arguments.len() < 3 {
-            println!("Usage: insert [artists, songs] [field1>; <field2>; ...]");
+        if command.arguments.len() < 2 {
+            println!("Usage: insert [artists, songs] [<field2> <field3> ...]");
             return;
         }
-        let table = (&command.arguments[0]).to_string();
+        let table = command.arguments[0].to_string();
 
         match table.as_str() {
             "artists" => {
-                if command.arguments.len() != 3 {
-                    println!("artists table consists of exactly 2 fields. Check your input and try again.");
-                    return;
-                }
-                let id = match command.arguments[1].parse::<i32>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        println!("{} is not a valid id!", &command.arguments[1]);
-                        return;
-                    }
-                };
-                let name = command.arguments[2].clone();
-
-                let artist = Artists {
-                    id: Int::new(id),
-                    name: Str::new(name),
+                let artist = match Self::parse_new_row::<Artists>(&command.arguments[1..]) {
+                    Ok(artist) => artist,
+                    Err(message) => {println!("{}", message); return}
                 };
-                match self.db.artists.create(artist) {
+                match self.db.artists.insert_auto(artist) {
                     Ok(_id) => println!("Created artist with id = {}", _id),
                     Err(e) => println!("Error: {}", e.message)
                 }
             },
             "songs" => {
-                if command.arguments.len() != 4 {
-                    println!("songs table consists of exactly 3 fields. Check your input and try again.");
-                    return;
-                }
-                let id = match command.arguments[1].parse::<i32>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        println!("{} is not a valid id!", &command.arguments[1]);
-                        return;
-                    }
-                };
-                let name = command.arguments[2].clone();
-                let artist_id = match command.arguments[3].parse::<i32>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        println!("{} is not a valid foreign key!", &command.arguments[1]);
-                        return;
-                    }
+                let song = match Self::parse_new_row::<Songs>(&command.arguments[1..]) {
+                    Ok(song) => song,
+                    Err(message) => {println!("{}", message); return}
                 };
 
                 // Check if given artist exists
-                match self.db.artists.get(artist_id.clone()) {
+                match song.artist(&mut self.db) {
                     Ok(_) => {},
                     Err(e) => {println!("Error: {}", e.message); return}
                 }
 
-                let song = Songs {
-                    id: Int::new(id),
-                    name: Str::new(name),
-                    artist_id: Int::new(artist_id)
-                };
-                match self.db.songs.create(song) {
+                match self.db.songs.insert_auto(song) {
                     Ok(_id) => println!("Created song with id = {}", _id),
                     Err(e) => println!("Error: {}", e.message)
                 }
             },
             table_name => {
                 println!("Table \"{}\" is not supported", table_name);
-                return;
             }
         }
     }
 
+    /// Builds a row to be inserted with `Table::insert_auto` out of command arguments, given
+    /// in the order of the table's fields except for the primary key.
+    fn parse_new_row<S: TableSchema>(arguments: &[String]) -> Result<S, String> {
+        let schema = S::schema();
+        if arguments.len() + 1 != schema.columns.len() {
+            return Err(format!(
+                "Expected values of {} fields ({}). Check your input and try again.",
+                schema.columns.len() - 1,
+                schema.columns
+                    .iter()
+                    .filter(|column| !column.primary_key)
+                    .map(|column| format!("{} {}", column.name, column.kind))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        // The id is assigned on insertion
+        let mut arguments = arguments.to_vec();
+        arguments.insert(schema.primary_key().ordinal, "0".to_string());
+        Self::parse_row(&arguments)
+    }
+
+    /// Builds a row out of command arguments, given in the order of the table's fields.
+    fn parse_row<S: TableSchema>(arguments: &[String]) -> Result<S, String> {
+        let columns = S::schema().columns;
+        if arguments.len() != columns.len() {
+            return Err(format!(
+                "Table consists of exactly {} fields ({}). Check your input and try again.",
+                columns.len(),
+                columns.iter().map(|column| format!("{} {}", column.name, column.kind)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        let values = columns.iter().map(|column| column.name.to_string()).zip(arguments.iter().cloned()).collect();
+        S::from_strings(values).map_err(|e| format!("Error: {}", e.message))
+    }
+
     fn delete(&mut self, command: Command) {
         if command.arguments.len() != 2 {
             println!("Usage: delete [artists, songs] <id>");
@@ -308,13 +272,13 @@ impl Playground {
         let id = match command.arguments[1].parse::<i32>() {
             Ok(val) => val,
             Err(_) => {
-                println!("{} is not a valid id!", &command.arguments[1]);
+                println!("{} is not a valid id!", command.arguments[1]);
                 return;
             }
         };
 
         // let record: Option<dyn TableSchema>;
-        match (&command.arguments[0]).as_str() {
+        match command.arguments[0].as_str() {
             "artists" => match &self.db.artists.delete(id) {
                 Ok(_) => println!("Successfully deleted!"),
                 Err(e) => println!("Error: {}", e.message)
@@ -325,7 +289,21 @@ impl Playground {
             },
             table_name => {
                 println!("Table \"{}\" is not supported", table_name);
-                return;
+            }
+        }
+    }
+
+    fn describe(&self, command: Command) {
+        if command.arguments.len() != 1 {
+            println!("Usage: describe [artists, songs]");
+            return;
+        }
+
+        match command.arguments[0].as_str() {
+            "artists" => println!("{}", Artists::schema()),
+            "songs" => println!("{}", Songs::schema()),
+            table_name => {
+                println!("Table \"{}\" is not supported", table_name);
             }
         }
     }
@@ -334,9 +312,10 @@ impl Playground {
         println!("List of available commands:\n\
                     \tlist [artists, songs]\n\
                     \tget [artists, songs] <id>\n\
-                    \tupdate [artists, songs] <id> [field1>; <field2>; ...]\n\
-                    \tinsert [artists, songs] [field1>; <field2>; ...]\n\
+                    \tupdate [artists, songs] <id> [<field2> <field3> ...]\n\
+                    \tinsert [artists, songs] [<field2> <field3> ...]\n\
                     \tdelete [artists, songs] <id>\n\
+                    \tdescribe [artists, songs]\n\
                     \thelp\n\
                     \texit\
         ");
@@ -1,70 +1,168 @@
-use std::collections::HashMap;
-use std::fmt::{Debug, Display, Formatter};
-use chrono;
-use chrono::Date;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use chrono::Datelike;
+
+use super::error::{Error, ErrorKind};
+
+#[cfg(feature = "serde")]
+mod serde_impls;
 
 
 pub trait DataType<T> {
     fn new(value: T) -> Self
         where Self: Sized;
-    fn from_(raw: &[u8]) -> Self
+    fn from_(raw: &[u8]) -> Result<Self, Error>
         where Self: Sized;
-    fn deserialize(raw: &[u8]) -> T
+    fn deserialize(raw: &[u8]) -> Result<T, Error>
         where Self: Sized;
 
     fn get(&self) -> T;
     fn serialize(&self) -> Box<[u8]>;
+
+    fn kind() -> DataKind
+        where Self: Sized;
+    /// Whether the column may hold a null; only `Json` can, as a JSON `null`.
+    fn nullable() -> bool
+        where Self: Sized
+    {
+        false
+    }
+
+    /// Parses the textual form produced by `format`.
+    fn parse(text: &str) -> Result<Self, Error>
+        where Self: Sized;
+    /// Formats the value as text; dates and times use ISO 8601.
+    fn format(&self) -> String;
+
+    /// Checks constraints of the type that `new` doesn't enforce; called before a value is
+    /// written and after it is read.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Datatype of a column with its parameters, see `DataType::kind`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum DataKind {
+    Int,
+    Str,
+    VarChar(usize),
+    Char(usize),
+    DateTime,
+    Bool,
+    BigInt,
+    Float,
+    Bytes,
+    Date,
+    Uuid,
+    Decimal { precision: u32, scale: u32 },
+    Timestamp,
+    /// `EnumType` column, with the enum's name.
+    Enum(&'static str),
+    List(Box<DataKind>),
+    Json,
+}
+
+/// Formats the kind the way the type is written in a schema, e.g. `VarChar<32>`.
+impl Display for DataKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataKind::VarChar(n) => write!(f, "VarChar<{}>", n),
+            DataKind::Char(n) => write!(f, "Char<{}>", n),
+            DataKind::Decimal { precision, scale } => write!(f, "Decimal<{}, {}>", precision, scale),
+            DataKind::Enum(name) => f.write_str(name),
+            DataKind::List(item) => write!(f, "List<{}>", item),
+            kind => write!(f, "{:?}", kind),
+        }
+    }
+}
+
+fn parse_error(text: &str, type_name: &str, reason: impl Display) -> Error {
+    Error {
+        kind: ErrorKind::Parse,
+        message: format!("{:?} is not a valid {}: {}", text, type_name, reason),
+    }
+}
+
+/// Converts `raw` into a fixed-size array, failing if its length doesn't match the
+/// encoding of `type_name`.
+fn fixed_size<const N: usize>(raw: &[u8], type_name: &str) -> Result<[u8; N], Error> {
+    raw.try_into().map_err(|_| Error {
+        kind: ErrorKind::Decode,
+        message: format!("expected {} bytes for {}, got {}", N, type_name, raw.len()),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Int {
     value: i32,
 }
 
 impl DataType<i32> for Int {
+    fn kind() -> DataKind {
+        DataKind::Int
+    }
+
     fn new(value: i32) -> Self {
         Self {value}
     }
 
-    fn from_(raw: &[u8]) -> Int {
-        Self {value: Int::deserialize(raw)}
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
     }
 
-    fn deserialize(raw: &[u8]) -> i32 {
-        i32::from_ne_bytes(raw.try_into().unwrap_or([0, 0, 0, 0]))
+    fn deserialize(raw: &[u8]) -> Result<i32, Error> {
+        Ok(i32::from_ne_bytes(fixed_size(raw, "Int")?))
     }
 
     fn get(&self) -> i32 {
-        self.value.clone()
+        self.value
     }
 
     fn serialize(&self) -> Box<[u8]> {
         self.value.to_ne_bytes().into()
     }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        text.parse().map(Self::new).map_err(|e| parse_error(text, "Int", e))
+    }
+
+    fn format(&self) -> String {
+        self.value.to_string()
+    }
 }
 
 impl Display for Int {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", &self.value)
+        write!(f, "{}", self.value)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Str {
     value: String,
 }
 
 impl DataType<String> for Str {
+    fn kind() -> DataKind {
+        DataKind::Str
+    }
+
     fn new(value: String) -> Str {
         Self {value}
     }
 
-    fn from_(raw: &[u8]) -> Self {
-        Self {value: Self::deserialize(raw)}
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
     }
 
-    fn deserialize(raw: &[u8]) -> String {
-        String::from_utf8(raw.into()).unwrap_or(String::from("#!ERR"))
+    fn deserialize(raw: &[u8]) -> Result<String, Error> {
+        String::from_utf8(raw.into()).map_err(|e| Error {
+            kind: ErrorKind::Decode,
+            message: format!("invalid UTF-8 in Str: {}", e),
+        })
     }
 
     fn get(&self) -> String {
@@ -74,119 +172,2260 @@ impl DataType<String> for Str {
     fn serialize(&self) -> Box<[u8]> {
         self.value.clone().into_bytes().into()
     }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        Ok(Self::new(text.to_string()))
+    }
+
+    fn format(&self) -> String {
+        self.value.clone()
+    }
 }
 
 impl Display for Str {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", &self.value)
+        write!(f, "{}", self.value)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Checks that `value` has at most `max_len` characters.
+fn check_length(value: &str, max_len: usize, type_name: &str) -> Result<(), Error> {
+    let len = value.chars().count();
+    if len > max_len {
+        return Err(Error {
+            kind: ErrorKind::Validation,
+            message: format!("value is {} characters long, {} allows at most {}", len, type_name, max_len),
+        });
+    }
+    Ok(())
+}
+
+/// String of at most `N` characters.
+///
+/// `new` accepts any string and the length is checked by `validate` on insert and update,
+/// use `try_new` to check it upfront.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VarChar<const N: usize> {
+    value: String,
+}
+
+impl<const N: usize> VarChar<N> {
+    pub fn try_new(value: String) -> Result<Self, Error> {
+        let instance = Self::new(value);
+        instance.validate()?;
+        Ok(instance)
+    }
+}
+
+impl<const N: usize> DataType<String> for VarChar<N> {
+    fn kind() -> DataKind {
+        DataKind::VarChar(N)
+    }
+
+    fn new(value: String) -> Self {
+        Self {value}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        let instance = Self {value: Str::deserialize(raw)?};
+        instance.validate()?;
+        Ok(instance)
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<String, Error> {
+        Ok(Self::from_(raw)?.value)
+    }
+
+    fn get(&self) -> String {
+        self.value.clone()
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        self.value.clone().into_bytes().into()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        check_length(&self.value, N, &format!("VarChar<{}>", N))
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        Self::try_new(text.to_string())
+    }
+
+    fn format(&self) -> String {
+        self.value.clone()
+    }
+}
+
+impl<const N: usize> Display for VarChar<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// String of exactly `N` characters, right-padded with spaces on construction.
+///
+/// Strings longer than `N` are kept intact by `new` and rejected by `validate`, like with
+/// `VarChar`; `from_` rejects stored values of any other length.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Char<const N: usize> {
+    value: String,
+}
+
+impl<const N: usize> Char<N> {
+    pub fn try_new(value: String) -> Result<Self, Error> {
+        let instance = Self::new(value);
+        instance.validate()?;
+        Ok(instance)
+    }
+}
+
+impl<const N: usize> DataType<String> for Char<N> {
+    fn kind() -> DataKind {
+        DataKind::Char(N)
+    }
+
+    fn new(value: String) -> Self {
+        Self {value: format!("{:<width$}", value, width = N)}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        let instance = Self {value: Str::deserialize(raw)?};
+        instance.validate()?;
+        Ok(instance)
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<String, Error> {
+        Ok(Self::from_(raw)?.value)
+    }
+
+    fn get(&self) -> String {
+        self.value.clone()
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        self.value.clone().into_bytes().into()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        check_length(&self.value, N, &format!("Char<{}>", N))?;
+        let len = self.value.chars().count();
+        if len < N {
+            return Err(Error {
+                kind: ErrorKind::Validation,
+                message: format!("value is {} characters long, Char<{}> requires exactly {}", len, N, N),
+            });
+        }
+        Ok(())
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        Self::try_new(text.to_string())
+    }
+
+    fn format(&self) -> String {
+        self.value.clone()
+    }
+}
+
+impl<const N: usize> Display for Char<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// Date and time without an offset, stored as nanoseconds since 1970 in 8 bytes, which
+/// limits it to 1677-09-21 through 2262-04-11; see `Timestamp` for a wider range.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DateTime {
     value: chrono::NaiveDateTime,
 }
 
+impl DateTime {
+    /// Like `new`, but fails if the value is outside of the range that can be stored.
+    pub fn try_new(value: chrono::NaiveDateTime) -> Result<Self, Error> {
+        let instance = Self::new(value);
+        instance.validate()?;
+        Ok(instance)
+    }
+
+    /// The stored form, `None` if it doesn't fit into an `i64`.
+    fn nanos(&self) -> Option<i64> {
+        let nanos = self.value.timestamp() as i128 * 1_000_000_000 + self.value.timestamp_subsec_nanos() as i128;
+        i64::try_from(nanos).ok()
+    }
+}
+
 impl DataType<chrono::NaiveDateTime> for DateTime {
+    fn kind() -> DataKind {
+        DataKind::DateTime
+    }
+
     fn new(value: chrono::NaiveDateTime) -> Self {
         Self {value}
     }
 
-    fn from_(raw: &[u8]) -> Self {
-        Self {value: Self::deserialize(raw)}
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
     }
 
-    fn deserialize(raw: &[u8]) -> chrono::NaiveDateTime {
-        let raw_: i64 = i64::from_ne_bytes(
-            raw.try_into().unwrap_or([0, 0, 0, 0, 0, 0, 0, 0])
-        );
-        chrono::NaiveDateTime::from_timestamp(
-            raw_ / 1_000_000_000,
-            (raw_  % 1_000_000_000) as u32,
-        )
+    fn deserialize(raw: &[u8]) -> Result<chrono::NaiveDateTime, Error> {
+        let raw_: i64 = i64::from_ne_bytes(fixed_size(raw, "DateTime")?);
+        chrono::NaiveDateTime::from_timestamp_opt(
+            raw_.div_euclid(1_000_000_000),
+            raw_.rem_euclid(1_000_000_000) as u32,
+        ).ok_or_else(|| Error {
+            kind: ErrorKind::Decode,
+            message: format!("{} is not a valid DateTime timestamp", raw_),
+        })
     }
 
     fn get(&self) -> chrono::NaiveDateTime {
-        self.value.clone()
+        self.value
     }
 
+    /// Panics for values rejected by `validate`, which `Table` checks before writing.
     fn serialize(&self) -> Box<[u8]> {
-        self.value.timestamp_nanos().to_ne_bytes().into()
+        self.nanos()
+            .unwrap_or_else(|| panic!("{} is out of DateTime range", self.value))
+            .to_ne_bytes()
+            .into()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        match self.nanos() {
+            Some(_) => Ok(()),
+            None => Err(Error {
+                kind: ErrorKind::OutOfRange,
+                message: format!("{} is out of DateTime range (1677-09-21 to 2262-04-11)", self.value),
+            }),
+        }
+    }
+
+    /// Accepts `2022-02-24T05:00:00` with optional fractional seconds; a space may be used
+    /// instead of `T`.
+    fn parse(text: &str) -> Result<Self, Error> {
+        let value = chrono::NaiveDateTime::parse_from_str(&text.replacen(' ', "T", 1), "%Y-%m-%dT%H:%M:%S%.f")
+            .map_err(|e| parse_error(text, "DateTime", e))?;
+        Self::try_new(value)
+    }
+
+    fn format(&self) -> String {
+        self.value.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
     }
 }
 
 impl Display for DateTime {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", &self.value)
+        write!(f, "{}", self.value)
     }
 }
 
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum DType {
-    Int(Int),
-    Str(Str),
-    DateTime(DateTime),
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bool {
+    value: bool,
 }
 
+impl DataType<bool> for Bool {
+    fn kind() -> DataKind {
+        DataKind::Bool
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::ops::Deref;
-    use super::*;
+    fn new(value: bool) -> Self {
+        Self {value}
+    }
 
-    #[test]
-    fn test_int() {
-        let num = 5;
-        let byte_array_be = [0u8, 0u8, 0u8, 5u8];
-        let byte_array_le = [5u8, 0u8, 0u8, 0u8];
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
 
-        let obj = Int::new(num);
+    fn deserialize(raw: &[u8]) -> Result<bool, Error> {
+        match raw {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(Error {
+                kind: ErrorKind::Decode,
+                message: format!("{:?} is not a valid Bool", raw),
+            }),
+        }
+    }
 
-        assert_eq!(obj.get(), num);
+    fn get(&self) -> bool {
+        self.value
+    }
 
-        if cfg!(target_endian = "big") {
-            assert_eq!(Int::deserialize(&byte_array_be), num);
-            assert_eq!(Int::from_(&byte_array_be).get(), num);
-            assert_eq!(obj.serialize().deref(), byte_array_be);
-        } else {
-            assert_eq!(Int::deserialize(&byte_array_le), num);
-            assert_eq!(Int::from_(&byte_array_le).get(), num);
-            assert_eq!(obj.serialize().deref(), byte_array_le);
+    fn serialize(&self) -> Box<[u8]> {
+        Box::from([self.value as u8])
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        match text {
+            "true" => Ok(Self::new(true)),
+            "false" => Ok(Self::new(false)),
+            _ => Err(parse_error(text, "Bool", "expected `true` or `false`")),
         }
     }
 
-    #[test]
-    fn test_str() {
-        let text: String = "Amour Plastique".into();
-        let byte_array = [65, 109, 111, 117, 114, 32, 80, 108, 97, 115, 116, 105, 113, 117, 101];
+    fn format(&self) -> String {
+        self.value.to_string()
+    }
+}
 
-        let obj = Str::new(text.clone());
-        assert_eq!(obj.get(), text.clone());
-        assert_eq!(obj.serialize().deref(), byte_array.clone());
-        assert_eq!(Str::deserialize(&byte_array), text.clone());
-        assert_eq!(Str::from_(&byte_array).get(), text.clone());
+impl Display for Bool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
     }
+}
 
-    #[test]
-    fn test_datetime() {
-        let datetime = chrono::NaiveDateTime::from_timestamp(60, 1024);
-        let byte_array_be = [0, 0, 0, 13, 248, 71, 92, 0];
-        let byte_array_le = [0, 92, 71, 248, 13, 0, 0, 0];
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BigInt {
+    value: i64,
+}
 
-        let obj = DateTime::new(datetime.clone());
+impl DataType<i64> for BigInt {
+    fn kind() -> DataKind {
+        DataKind::BigInt
+    }
 
-        assert_eq!(obj.get(), datetime);
+    fn new(value: i64) -> Self {
+        Self {value}
+    }
 
-        if cfg!(target_endian = "big") {
-            assert_eq!(DateTime::deserialize(&byte_array_be), datetime);
-            assert_eq!(DateTime::from_(&byte_array_be).get(), datetime);
-            assert_eq!(obj.serialize().deref(), byte_array_be);
-        } else {
-            assert_eq!(DateTime::deserialize(&byte_array_le), datetime);
-            assert_eq!(DateTime::from_(&byte_array_le).get(), datetime);
-            assert_eq!(obj.serialize().deref(), byte_array_le);
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<i64, Error> {
+        Ok(i64::from_ne_bytes(fixed_size(raw, "BigInt")?))
+    }
+
+    fn get(&self) -> i64 {
+        self.value
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        self.value.to_ne_bytes().into()
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        text.parse().map(Self::new).map_err(|e| parse_error(text, "BigInt", e))
+    }
+
+    fn format(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// 64-bit floating point number.
+///
+/// Equality and ordering are defined by `f64::total_cmp`, so `NaN` equals itself and sorts
+/// above infinity, and `-0.0` sorts below `0.0`. This keeps `Float` (and therefore `DType`)
+/// `Eq` and `Ord`.
+#[derive(Debug, Clone)]
+pub struct Float {
+    value: f64,
+}
+
+impl DataType<f64> for Float {
+    fn kind() -> DataKind {
+        DataKind::Float
+    }
+
+    fn new(value: f64) -> Self {
+        Self {value}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<f64, Error> {
+        Ok(f64::from_ne_bytes(fixed_size(raw, "Float")?))
+    }
+
+    fn get(&self) -> f64 {
+        self.value
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        self.value.to_ne_bytes().into()
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        text.parse().map(Self::new).map_err(|e| parse_error(text, "Float", e))
+    }
+
+    fn format(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Float {}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.total_cmp(&other.value)
+    }
+}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Float {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bytes {
+    value: Vec<u8>,
+}
+
+impl DataType<Vec<u8>> for Bytes {
+    fn kind() -> DataKind {
+        DataKind::Bytes
+    }
+
+    fn new(value: Vec<u8>) -> Self {
+        Self {value}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(raw.to_vec())
+    }
+
+    fn get(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        self.value.clone().into_boxed_slice()
+    }
+
+    /// Expects hex digits, two per byte.
+    fn parse(text: &str) -> Result<Self, Error> {
+        if !text.len().is_multiple_of(2) {
+            return Err(parse_error(text, "Bytes", "odd number of hex digits"));
         }
+        (0..text.len())
+            .step_by(2)
+            .map(|i| {
+                text.get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(|| parse_error(text, "Bytes", "expected hex digits"))
+            })
+            .collect::<Result<Vec<u8>, Error>>()
+            .map(Self::new)
+    }
+
+    fn format(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for byte in &self.value {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Calendar date, stored as the number of days since 0001-01-01.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    value: chrono::NaiveDate,
+}
+
+impl DataType<chrono::NaiveDate> for Date {
+    fn kind() -> DataKind {
+        DataKind::Date
+    }
+
+    fn new(value: chrono::NaiveDate) -> Self {
+        Self {value}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<chrono::NaiveDate, Error> {
+        let days = i32::from_ne_bytes(fixed_size(raw, "Date")?);
+        // chrono shifts `days` by 365 internally and overflows near `i32::MAX`
+        days.checked_add(365)
+            .and_then(|_| chrono::NaiveDate::from_num_days_from_ce_opt(days))
+            .ok_or_else(|| Error {
+                kind: ErrorKind::Decode,
+                message: format!("{} is out of Date range", days),
+            })
+    }
+
+    fn get(&self) -> chrono::NaiveDate {
+        self.value
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        self.value.num_days_from_ce().to_ne_bytes().into()
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(Self::new)
+            .map_err(|e| parse_error(text, "Date", e))
+    }
+
+    fn format(&self) -> String {
+        self.value.format("%Y-%m-%d").to_string()
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Uuid {
+    value: uuid::Uuid,
+}
+
+impl DataType<uuid::Uuid> for Uuid {
+    fn kind() -> DataKind {
+        DataKind::Uuid
+    }
+
+    fn new(value: uuid::Uuid) -> Self {
+        Self {value}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<uuid::Uuid, Error> {
+        Ok(uuid::Uuid::from_bytes(fixed_size(raw, "Uuid")?))
+    }
+
+    fn get(&self) -> uuid::Uuid {
+        self.value
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        Box::from(*self.value.as_bytes())
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        uuid::Uuid::parse_str(text).map(Self::new).map_err(|e| parse_error(text, "Uuid", e))
+    }
+
+    fn format(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+impl Display for Uuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+
+/// Exact decimal number as a `mantissa` scaled down by `10^scale`, e.g. `(12345, 2)` is
+/// `123.45`. This is the value behind every `Decimal<P, S>` column, regardless of its
+/// precision, so values of different columns can be compared and combined.
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalValue {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl DecimalValue {
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Self {mantissa, scale}
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Number of significant digits in the mantissa.
+    pub fn digits(&self) -> u32 {
+        let mut digits = 1;
+        let mut rest = self.mantissa.unsigned_abs() / 10;
+        while rest > 0 {
+            digits += 1;
+            rest /= 10;
+        }
+        digits
+    }
+
+    /// Returns the same value expressed with `scale` fractional digits, or `None` if that
+    /// would lose digits or overflow.
+    pub fn rescale(&self, scale: u32) -> Option<Self> {
+        let mantissa = if scale >= self.scale {
+            self.mantissa.checked_mul(10i128.checked_pow(scale - self.scale)?)?
+        } else {
+            let divisor = 10i128.checked_pow(self.scale - scale)?;
+            if self.mantissa % divisor != 0 {
+                return None;
+            }
+            self.mantissa / divisor
+        };
+        Some(Self {mantissa, scale})
+    }
+
+    /// Rounds to `scale` fractional digits, half away from zero.
+    pub fn round(&self, scale: u32) -> Self {
+        if scale >= self.scale {
+            return self.rescale(scale).unwrap_or(*self);
+        }
+        let divisor = match 10i128.checked_pow(self.scale - scale) {
+            Some(divisor) => divisor,
+            // Every mantissa is below half of such a divisor
+            None => return Self {mantissa: 0, scale},
+        };
+        let (quotient, remainder) = (self.mantissa / divisor, self.mantissa % divisor);
+        let mantissa = if remainder.unsigned_abs() * 2 >= divisor.unsigned_abs() {
+            quotient + self.mantissa.signum()
+        } else {
+            quotient
+        };
+        Self {mantissa, scale}
+    }
+
+    /// Brings both values to the larger of their scales.
+    fn align(&self, other: &Self) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        Some((self.rescale(scale)?.mantissa, other.rescale(scale)?.mantissa, scale))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        Some(Self {mantissa: a.checked_add(b)?, scale})
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        Some(Self {mantissa: a.checked_sub(b)?, scale})
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            mantissa: self.mantissa.checked_mul(other.mantissa)?,
+            scale: self.scale.checked_add(other.scale)?,
+        })
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self {mantissa: self.mantissa.checked_neg()?, scale: self.scale})
+    }
+
+    /// Exact sum of `values` for aggregations, or `None` if it overflows.
+    pub fn checked_sum(values: impl IntoIterator<Item = Self>) -> Option<Self> {
+        values.into_iter().try_fold(Self::new(0, 0), |sum, value| sum.checked_add(&value))
+    }
+}
+
+impl Ord for DecimalValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let Some((a, b, _)) = self.align(other) {
+            return a.cmp(&b);
+        }
+        let sign = self.mantissa.signum().cmp(&other.mantissa.signum());
+        if sign != Ordering::Equal || self.mantissa == 0 {
+            return sign;
+        }
+        // A magnitude lies in [10^(exponent - 1), 10^exponent); with equal exponents the
+        // scales differ by fewer digits than a mantissa has, so aligning stays cheap
+        let exponent = |value: &Self| value.digits() as i64 - value.scale as i64;
+        let magnitude = exponent(self).cmp(&exponent(other)).then_with(|| {
+            let aligned = |value: &Self| {
+                let mut aligned = BigUint::from(value.mantissa.unsigned_abs());
+                for _ in value.scale..self.scale.max(other.scale) {
+                    aligned.mul_small(10);
+                }
+                aligned
+            };
+            aligned(self).cmp(&aligned(other))
+        });
+        if self.mantissa < 0 { magnitude.reverse() } else { magnitude }
+    }
+}
+
+impl PartialOrd for DecimalValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for DecimalValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DecimalValue {}
+
+/// Parses plain decimal notation such as `-123.45`, keeping every fractional digit.
+impl std::str::FromStr for DecimalValue {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let error = |reason: &str| parse_error(text, "Decimal", reason);
+
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(error("no digits"));
+        }
+        if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(error("expected digits and an optional `.`"));
+        }
+
+        let mantissa: i128 = format!("{}{}", integer, fraction)
+            .parse()
+            .map_err(|_| error("too many digits"))?;
+        let mantissa = if negative { -mantissa } else { mantissa };
+        Ok(Self::new(mantissa, fraction.len() as u32))
+    }
+}
+
+impl Display for DecimalValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+/// Fixed-point decimal column with `P` significant digits, `S` of which follow the point
+/// (like SQL `DECIMAL(P, S)`).
+///
+/// The mantissa is stored in 4, 8 or 16 bytes depending on `P`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decimal<const P: u32, const S: u32> {
+    value: DecimalValue,
+}
+
+impl<const P: u32, const S: u32> Decimal<P, S> {
+    const VALID: () = assert!(P >= 1 && P <= 38 && S <= P, "Decimal<P, S> requires 1 <= P <= 38 and S <= P");
+
+    fn width() -> usize {
+        match P {
+            0..=9 => 4,
+            10..=18 => 8,
+            _ => 16,
+        }
+    }
+
+    /// Like `new`, but fails if `value` doesn't fit into `P` digits or has more than `S`
+    /// fractional digits.
+    pub fn try_new(value: DecimalValue) -> Result<Self, Error> {
+        let instance = Self::new(value);
+        instance.validate()?;
+        Ok(instance)
+    }
+}
+
+impl<const P: u32, const S: u32> DataType<DecimalValue> for Decimal<P, S> {
+    fn kind() -> DataKind {
+        DataKind::Decimal { precision: P, scale: S }
+    }
+
+    /// Brings `value` to scale `S`; a value that doesn't fit is kept as is and rejected by
+    /// `validate`.
+    fn new(value: DecimalValue) -> Self {
+        let () = Self::VALID;
+        Self {value: value.rescale(S).unwrap_or(value)}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<DecimalValue, Error> {
+        let () = Self::VALID;
+        let type_name = format!("Decimal<{}, {}>", P, S);
+        let mantissa = match Self::width() {
+            4 => i32::from_ne_bytes(fixed_size(raw, &type_name)?) as i128,
+            8 => i64::from_ne_bytes(fixed_size(raw, &type_name)?) as i128,
+            _ => i128::from_ne_bytes(fixed_size(raw, &type_name)?),
+        };
+        let value = DecimalValue::new(mantissa, S);
+        if value.digits() > P {
+            return Err(Error {
+                kind: ErrorKind::Decode,
+                message: format!("{} has more than {} digits", value, P),
+            });
+        }
+        Ok(value)
+    }
+
+    fn get(&self) -> DecimalValue {
+        self.value
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        // The mantissa of a valid value always fits into the width chosen for `P` digits
+        match Self::width() {
+            4 => (self.value.mantissa as i32).to_ne_bytes().into(),
+            8 => (self.value.mantissa as i64).to_ne_bytes().into(),
+            _ => self.value.mantissa.to_ne_bytes().into(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.value.scale == S && self.value.digits() <= P {
+            return Ok(());
+        }
+        Err(Error {
+            kind: ErrorKind::OutOfRange,
+            message: format!("{} doesn't fit into Decimal<{}, {}>", self.value, P, S),
+        })
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        Self::try_new(text.parse()?)
+    }
+
+    fn format(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+impl<const P: u32, const S: u32> Display for Decimal<P, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+
+/// Instant in time, stored in UTC together with the offset it was recorded in (if known).
+///
+/// Unlike `DateTime`, the encoding (seconds, nanoseconds and offset, 16 bytes) covers the
+/// whole range supported by chrono rather than ±292 years around 1970.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timestamp {
+    value: chrono::DateTime<chrono::Utc>,
+    offset: Option<chrono::FixedOffset>,
+}
+
+impl Timestamp {
+    /// Marks a missing offset in the on-disk encoding.
+    const NO_OFFSET: i32 = i32::MIN;
+
+    /// Creates a timestamp remembering the offset of `value`.
+    pub fn with_offset(value: chrono::DateTime<chrono::FixedOffset>) -> Self {
+        Self {
+            value: value.with_timezone(&chrono::Utc),
+            offset: Some(*value.offset()),
+        }
+    }
+
+    /// Interprets `value` as UTC.
+    pub fn from_naive_utc(value: chrono::NaiveDateTime) -> Self {
+        Self::new(chrono::DateTime::from_utc(value, chrono::Utc))
+    }
+
+    pub fn now() -> Self {
+        Self::new(chrono::Utc::now())
+    }
+
+    pub fn offset(&self) -> Option<chrono::FixedOffset> {
+        self.offset
+    }
+
+    pub fn naive_utc(&self) -> chrono::NaiveDateTime {
+        self.value.naive_utc()
+    }
+
+    /// The instant in its recorded offset, or in UTC if none was recorded.
+    pub fn local(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        let offset = self.offset.unwrap_or_else(|| chrono::FixedOffset::east(0));
+        self.value.with_timezone(&offset)
+    }
+
+    pub fn to_offset(&self, offset: chrono::FixedOffset) -> chrono::DateTime<chrono::FixedOffset> {
+        self.value.with_timezone(&offset)
+    }
+}
+
+impl DataType<chrono::DateTime<chrono::Utc>> for Timestamp {
+    fn kind() -> DataKind {
+        DataKind::Timestamp
+    }
+
+    fn new(value: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {value, offset: None}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        let raw: [u8; 16] = fixed_size(raw, "Timestamp")?;
+        let offset = match i32::from_ne_bytes(fixed_size(&raw[12..], "Timestamp")?) {
+            Self::NO_OFFSET => None,
+            seconds => Some(chrono::FixedOffset::east_opt(seconds).ok_or_else(|| Error {
+                kind: ErrorKind::Decode,
+                message: format!("{} is not a valid Timestamp offset", seconds),
+            })?),
+        };
+        Ok(Self {value: Self::deserialize(&raw)?, offset})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<chrono::DateTime<chrono::Utc>, Error> {
+        let raw: [u8; 16] = fixed_size(raw, "Timestamp")?;
+        let seconds = i64::from_ne_bytes(fixed_size(&raw[..8], "Timestamp")?);
+        let nanos = u32::from_ne_bytes(fixed_size(&raw[8..12], "Timestamp")?);
+        match chrono::NaiveDateTime::from_timestamp_opt(seconds, nanos) {
+            Some(value) => Ok(chrono::DateTime::from_utc(value, chrono::Utc)),
+            None => Err(Error {
+                kind: ErrorKind::Decode,
+                message: format!("{}s {}ns is out of Timestamp range", seconds, nanos),
+            }),
+        }
+    }
+
+    fn get(&self) -> chrono::DateTime<chrono::Utc> {
+        self.value
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        let offset = match self.offset {
+            Some(offset) => offset.local_minus_utc(),
+            None => Self::NO_OFFSET,
+        };
+        let mut raw = Vec::with_capacity(16);
+        raw.extend_from_slice(&self.value.timestamp().to_ne_bytes());
+        raw.extend_from_slice(&self.value.timestamp_subsec_nanos().to_ne_bytes());
+        raw.extend_from_slice(&offset.to_ne_bytes());
+        raw.into_boxed_slice()
+    }
+
+    /// Expects RFC 3339; a `Z` suffix is read as "no offset recorded" rather than an
+    /// explicit `+00:00`.
+    fn parse(text: &str) -> Result<Self, Error> {
+        let value = chrono::DateTime::parse_from_rfc3339(text)
+            .map_err(|e| parse_error(text, "Timestamp", e))?;
+        if text.ends_with('Z') || text.ends_with('z') {
+            Ok(Self::new(value.with_timezone(&chrono::Utc)))
+        } else {
+            Ok(Self::with_offset(value))
+        }
+    }
+
+    fn format(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Orders by instant, then by offset so that the order agrees with equality.
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let offset = |timestamp: &Self| timestamp.offset.map(|offset| offset.local_minus_utc());
+        self.value.cmp(&other.value).then_with(|| offset(self).cmp(&offset(other)))
+    }
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<DateTime> for Timestamp {
+    fn from(value: DateTime) -> Self {
+        Self::from_naive_utc(value.get())
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let formatted = self.local().to_rfc3339_opts(chrono::SecondsFormat::AutoSi, self.offset.is_none());
+        write!(f, "{}", formatted)
+    }
+}
+
+
+/// Variable-length list of values of another datatype.
+///
+/// Encoded as the number of items followed by every item's length and serialized bytes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct List<T> {
+    items: Vec<T>,
+}
+
+impl<T> List<T> {
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<V, T: DataType<V>> DataType<Vec<V>> for List<T> {
+    fn kind() -> DataKind {
+        DataKind::List(Box::new(T::kind()))
+    }
+
+    fn new(value: Vec<V>) -> Self {
+        Self {items: value.into_iter().map(T::new).collect()}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        let mut rest = raw;
+        let mut take = |size: usize| -> Result<&[u8], Error> {
+            if rest.len() < size {
+                return Err(Error {
+                    kind: ErrorKind::Decode,
+                    message: format!("List is truncated after {} bytes", raw.len() - rest.len()),
+                });
+            }
+            let (taken, remaining) = rest.split_at(size);
+            rest = remaining;
+            Ok(taken)
+        };
+
+        let count = u32::from_ne_bytes(fixed_size(take(4)?, "List")?);
+        // Every item takes at least its 4-byte size prefix, so a larger count can't be valid
+        // and must not be trusted for the allocation below.
+        if count as usize > (raw.len() - 4) / 4 {
+            return Err(Error {
+                kind: ErrorKind::Decode,
+                message: format!("List claims {} items but has only {} bytes", count, raw.len() - 4),
+            });
+        }
+        let mut items = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let size = u32::from_ne_bytes(fixed_size(take(4)?, "List")?);
+            let item = T::from_(take(size as usize)?).map_err(|e| Error {
+                kind: e.kind,
+                message: format!("List item {}: {}", i, e.message),
+            })?;
+            items.push(item);
+        }
+        if !rest.is_empty() {
+            return Err(Error {
+                kind: ErrorKind::Decode,
+                message: format!("{} unexpected bytes after List items", rest.len()),
+            });
+        }
+        Ok(Self {items})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<Vec<V>, Error> {
+        Ok(Self::from_(raw)?.get())
+    }
+
+    fn get(&self) -> Vec<V> {
+        self.items.iter().map(|item| item.get()).collect()
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&(self.items.len() as u32).to_ne_bytes());
+        for item in &self.items {
+            let item = item.serialize();
+            raw.extend_from_slice(&(item.len() as u32).to_ne_bytes());
+            raw.extend_from_slice(&item);
+        }
+        raw.into_boxed_slice()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        for (i, item) in self.items.iter().enumerate() {
+            item.validate().map_err(|e| Error {
+                kind: e.kind,
+                message: format!("List item {}: {}", i, e.message),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Expects a JSON array of the items' textual forms, e.g. `["indie", "rock"]`.
+    fn parse(text: &str) -> Result<Self, Error> {
+        let items: Vec<String> = serde_json::from_str(text)
+            .map_err(|e| parse_error(text, "List", e))?;
+        Ok(Self {items: items.iter().map(|item| T::parse(item)).collect::<Result<_, _>>()?})
+    }
+
+    fn format(&self) -> String {
+        let items: Vec<String> = self.items.iter().map(|item| item.format()).collect();
+        serde_json::Value::from(items).to_string()
+    }
+}
+
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, item) in self.items.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        write!(f, "]")
+    }
+}
+
+
+/// JSON document, stored as validated JSON text.
+///
+/// Equality follows the ordering, so numbers are equal by value, e.g. `1` and `1.0`.
+#[derive(Debug, Clone)]
+pub struct Json {
+    value: serde_json::Value,
+}
+
+impl Json {
+    /// Looks up the value at `path`, e.g. `$.stats.plays` or `$.artists[0]`.
+    pub fn path(&self, path: &JsonPath) -> Option<&serde_json::Value> {
+        path.lookup(&self.value)
+    }
+}
+
+impl DataType<serde_json::Value> for Json {
+    fn kind() -> DataKind {
+        DataKind::Json
+    }
+
+    fn nullable() -> bool {
+        true
+    }
+
+    fn new(value: serde_json::Value) -> Self {
+        Self {value}
+    }
+
+    fn from_(raw: &[u8]) -> Result<Self, Error> {
+        Ok(Self {value: Self::deserialize(raw)?})
+    }
+
+    fn deserialize(raw: &[u8]) -> Result<serde_json::Value, Error> {
+        serde_json::from_slice(raw).map_err(|e| Error {
+            kind: ErrorKind::Decode,
+            message: format!("invalid Json: {}", e),
+        })
+    }
+
+    fn get(&self) -> serde_json::Value {
+        self.value.clone()
+    }
+
+    fn serialize(&self) -> Box<[u8]> {
+        self.value.to_string().into_bytes().into()
+    }
+
+    fn parse(text: &str) -> Result<Self, Error> {
+        serde_json::from_str(text).map(Self::new).map_err(|e| parse_error(text, "Json", e))
+    }
+
+    fn format(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+/// Orders `null < false < true < numbers < strings < arrays < objects`, comparing arrays
+/// item by item and objects by their sorted entries.
+fn compare_json(a: &serde_json::Value, b: &serde_json::Value) -> Ordering {
+    use serde_json::Value;
+
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => compare_json_numbers(a, b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| compare_json(a, b))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Object(a), Value::Object(b)) => {
+            let mut a: Vec<_> = a.iter().collect();
+            let mut b: Vec<_> = b.iter().collect();
+            a.sort_by(|x, y| x.0.cmp(y.0));
+            b.sort_by(|x, y| x.0.cmp(y.0));
+            a.iter()
+                .zip(b.iter())
+                .map(|(a, b)| a.0.cmp(b.0).then_with(|| compare_json(a.1, b.1)))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| a.len().cmp(&b.len()))
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Compares by value exactly, integers with floats included.
+fn compare_json_numbers(a: &serde_json::Number, b: &serde_json::Number) -> Ordering {
+    let integer = |number: &serde_json::Number| number.as_i64().map(i128::from).or_else(|| number.as_u64().map(i128::from));
+    // JSON numbers are finite, so `as_f64` only fails for integers
+    let float = |number: &serde_json::Number| number.as_f64().unwrap_or(f64::NAN);
+    match (integer(a), integer(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(a), None) => cmp_decimal_float(DecimalValue::new(a, 0), float(b)),
+        (None, Some(b)) => cmp_decimal_float(DecimalValue::new(b, 0), float(a)).reverse(),
+        (None, None) if float(a) == float(b) => Ordering::Equal,
+        (None, None) => float(a).total_cmp(&float(b)),
+    }
+}
+
+impl Ord for Json {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_json(&self.value, &other.value)
+    }
+}
+
+impl PartialEq for Json {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Json {}
+
+impl PartialOrd for Json {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Location inside a JSON document: `$` followed by `.key`, `["key"]` and `[index]` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    segments: Vec<JsonPathSegment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, Error> {
+        let error = |reason: &str| Error {
+            kind: ErrorKind::Parse,
+            message: format!("invalid JSON path {:?}: {}", path, reason),
+        };
+
+        let mut rest = path.strip_prefix('$').ok_or_else(|| error("must start with `$`"))?;
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix('.') {
+                let end = tail.find(['.', '[']).unwrap_or(tail.len());
+                if end == 0 {
+                    return Err(error("empty key"));
+                }
+                segments.push(JsonPathSegment::Key(tail[..end].to_string()));
+                rest = &tail[end..];
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let end = tail.find(']').ok_or_else(|| error("unclosed `[`"))?;
+                let step = &tail[..end];
+                let segment = match step.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                    Some(key) => JsonPathSegment::Key(key.to_string()),
+                    None => JsonPathSegment::Index(step.parse().map_err(|_| error("invalid index"))?),
+                };
+                segments.push(segment);
+                rest = &tail[end + 1..];
+            } else {
+                return Err(error("expected `.` or `[`"));
+            }
+        }
+
+        Ok(Self {segments})
+    }
+
+    pub fn lookup<'a>(&self, value: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
+        self.segments.iter().try_fold(value, |value, segment| match segment {
+            JsonPathSegment::Key(key) => value.get(key),
+            JsonPathSegment::Index(index) => value.get(index),
+        })
+    }
+}
+
+impl Display for JsonPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "$")?;
+        for segment in &self.segments {
+            match segment {
+                JsonPathSegment::Key(key) => write!(f, "[{:?}]", key)?,
+                JsonPathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+
+/// Datatype able to hold the current time, as filled in for `#[versebase(auto_now)]` and
+/// `#[versebase(auto_now_add)]` columns.
+pub trait AutoNow {
+    fn now() -> Self;
+}
+
+impl AutoNow for DateTime {
+    /// Current time in UTC.
+    fn now() -> Self {
+        Self::new(chrono::Utc::now().naive_utc())
+    }
+}
+
+impl AutoNow for Date {
+    /// Current date in UTC.
+    fn now() -> Self {
+        Self::new(chrono::Utc::now().naive_utc().date())
+    }
+}
+
+impl AutoNow for Timestamp {
+    fn now() -> Self {
+        Timestamp::now()
+    }
+}
+
+
+/// Fieldless Rust enum usable as a column, implemented by `#[derive(VersebaseEnum)]`.
+///
+/// Values are stored as the variant's discriminant and checked against the known
+/// variants on read.
+pub trait EnumType: Sized {
+    fn type_name() -> &'static str;
+    fn from_discriminant(discriminant: i32) -> Option<Self>;
+
+    fn discriminant(&self) -> i32;
+    fn variant_name(&self) -> &'static str;
+}
+
+/// Type-erased value of an `EnumType` column, as found in `DType::Enum`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumValue {
+    type_name: Cow<'static, str>,
+    variant: Cow<'static, str>,
+    discriminant: i32,
+}
+
+impl EnumValue {
+    pub fn new<E: EnumType>(value: &E) -> Self {
+        Self {
+            type_name: Cow::Borrowed(E::type_name()),
+            variant: Cow::Borrowed(value.variant_name()),
+            discriminant: value.discriminant(),
+        }
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn variant(&self) -> &str {
+        &self.variant
+    }
+
+    pub fn discriminant(&self) -> i32 {
+        self.discriminant
+    }
+}
+
+/// Orders values of the same enum by discriminant.
+impl Ord for EnumValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.type_name.cmp(&other.type_name).then(self.discriminant.cmp(&other.discriminant))
+    }
+}
+
+impl PartialOrd for EnumValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for EnumValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.variant)
+    }
+}
+
+/// Decodes the discriminant of an `EnumType` column; used by `#[derive(VersebaseEnum)]`.
+pub fn deserialize_enum<E: EnumType>(raw: &[u8]) -> Result<E, Error> {
+    let discriminant = i32::from_ne_bytes(fixed_size(raw, E::type_name())?);
+    E::from_discriminant(discriminant).ok_or_else(|| Error {
+        kind: ErrorKind::Decode,
+        message: format!("{} is not a valid {} discriminant", discriminant, E::type_name()),
+    })
+}
+
+
+/// Value of any column.
+///
+/// Values compare across variants where it makes sense: `Int`, `BigInt`, `Decimal` and
+/// `Float` compare numerically (exactly, unless a `Float` is involved), and `Date`,
+/// `DateTime` and `Timestamp` compare as UTC instants (dates at midnight, `DateTime` taken
+/// as UTC). Other combinations are ordered by kind:
+/// `Bool < numbers < Str < Bytes < Uuid < dates and times < Enum < List < Json`.
+/// Equality follows the same rules, so `DType::Int` 2 equals `DType::BigInt` 2.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DType {
+    Int(Int),
+    Str(Str),
+    DateTime(DateTime),
+    Bool(Bool),
+    BigInt(BigInt),
+    Float(Float),
+    Bytes(Bytes),
+    Date(Date),
+    Uuid(Uuid),
+    Decimal(DecimalValue),
+    Timestamp(Timestamp),
+    Enum(EnumValue),
+    List(Vec<DType>),
+    Json(Json),
+}
+
+impl From<Int> for DType {
+    fn from(value: Int) -> Self {
+        DType::Int(value)
+    }
+}
+
+impl From<Str> for DType {
+    fn from(value: Str) -> Self {
+        DType::Str(value)
+    }
+}
+
+impl DType {
+    fn rank(&self) -> u8 {
+        match self {
+            DType::Bool(_) => 0,
+            DType::Int(_) | DType::BigInt(_) | DType::Decimal(_) | DType::Float(_) => 1,
+            DType::Str(_) => 2,
+            DType::Bytes(_) => 3,
+            DType::Uuid(_) => 4,
+            DType::Date(_) | DType::DateTime(_) | DType::Timestamp(_) => 5,
+            DType::Enum(_) => 6,
+            DType::List(_) => 7,
+            DType::Json(_) => 8,
+        }
+    }
+
+    /// Exact value of an integer or decimal variant.
+    fn as_decimal(&self) -> Option<DecimalValue> {
+        match self {
+            DType::Int(value) => Some(DecimalValue::new(value.get() as i128, 0)),
+            DType::BigInt(value) => Some(DecimalValue::new(value.get() as i128, 0)),
+            DType::Decimal(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_instant(&self) -> Option<chrono::NaiveDateTime> {
+        match self {
+            DType::Date(value) => Some(value.get().and_hms(0, 0, 0)),
+            DType::DateTime(value) => Some(value.get()),
+            DType::Timestamp(value) => Some(value.naive_utc()),
+            _ => None,
+        }
+    }
+
+    /// Formats the wrapped value like its `DataType::format`; `List` items are formatted
+    /// as a JSON array of strings.
+    pub fn format(&self) -> String {
+        match self {
+            DType::Int(value) => value.format(),
+            DType::Str(value) => value.format(),
+            DType::DateTime(value) => value.format(),
+            DType::Bool(value) => value.format(),
+            DType::BigInt(value) => value.format(),
+            DType::Float(value) => value.format(),
+            DType::Bytes(value) => value.format(),
+            DType::Date(value) => value.format(),
+            DType::Uuid(value) => value.format(),
+            DType::Decimal(value) => value.to_string(),
+            DType::Timestamp(value) => value.format(),
+            DType::Enum(value) => value.variant().to_string(),
+            DType::List(items) => {
+                let items: Vec<String> = items.iter().map(|item| item.format()).collect();
+                serde_json::Value::from(items).to_string()
+            }
+            DType::Json(value) => value.format(),
+        }
+    }
+}
+
+/// Compares `decimal` with `float` exactly; NaNs sort outside of the numbers like they do
+/// in `f64::total_cmp`.
+fn cmp_decimal_float(decimal: DecimalValue, float: f64) -> Ordering {
+    if float.is_nan() || float.is_infinite() {
+        return if float.is_sign_negative() { Ordering::Greater } else { Ordering::Less };
+    }
+    let float_sign = if float == 0.0 { 0 } else if float < 0.0 { -1 } else { 1 };
+    let decimal_sign = decimal.mantissa.signum() as i32;
+    if decimal_sign != float_sign || decimal_sign == 0 {
+        return decimal_sign.cmp(&float_sign);
+    }
+
+    // `|float|` is `fraction * 2^exponent`, so comparing it with `|mantissa| / 10^scale`
+    // means comparing `|mantissa|` with `fraction * 5^scale * 2^(scale + exponent)`.
+    let bits = float.to_bits();
+    let (fraction, exponent) = match (bits >> 52) & 0x7ff {
+        0 => (bits & 0xf_ffff_ffff_ffff, -1074),
+        biased => (bits & 0xf_ffff_ffff_ffff | 1 << 52, biased as i64 - 1075),
+    };
+    let mut left = BigUint::from(decimal.mantissa.unsigned_abs());
+    let mut right = BigUint::from(fraction as u128);
+    for _ in 0..decimal.scale {
+        right.mul_small(5);
+    }
+    match decimal.scale as i64 + exponent {
+        shift if shift >= 0 => right.shl(shift as usize),
+        shift => left.shl(-shift as usize),
+    }
+    let magnitude = left.cmp(&right);
+    if decimal_sign < 0 { magnitude.reverse() } else { magnitude }
+}
+
+/// Just enough of an unsigned big integer for `cmp_decimal_float`: little-endian 32-bit
+/// limbs without high zero limbs.
+#[derive(PartialEq, Eq)]
+struct BigUint(Vec<u32>);
+
+impl BigUint {
+    fn from(value: u128) -> Self {
+        let mut limbs: Vec<u32> = (0..4).map(|i| (value >> (32 * i)) as u32).collect();
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self(limbs)
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in self.0.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry > 0 {
+            self.0.push(carry as u32);
+        }
+    }
+
+    fn shl(&mut self, bits: usize) {
+        if self.0.is_empty() {
+            return;
+        }
+        let (limbs, bits) = (bits / 32, bits % 32);
+        if bits > 0 {
+            let mut carry = 0;
+            for limb in self.0.iter_mut() {
+                let shifted = ((*limb as u64) << bits) | carry;
+                *limb = shifted as u32;
+                carry = shifted >> 32;
+            }
+            if carry > 0 {
+                self.0.push(carry as u32);
+            }
+        }
+        self.0.splice(0..0, std::iter::repeat_n(0, limbs));
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.len().cmp(&other.0.len()).then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (DType::Int(a), DType::Int(b)) => a.cmp(b),
+            (DType::Str(a), DType::Str(b)) => a.cmp(b),
+            (DType::DateTime(a), DType::DateTime(b)) => a.cmp(b),
+            (DType::Bool(a), DType::Bool(b)) => a.cmp(b),
+            (DType::BigInt(a), DType::BigInt(b)) => a.cmp(b),
+            // Both zeros equal `Int(0)`, so they must equal each other too
+            (DType::Float(a), DType::Float(b)) if a.get() == 0.0 && b.get() == 0.0 => Ordering::Equal,
+            (DType::Float(a), DType::Float(b)) => a.cmp(b),
+            (DType::Bytes(a), DType::Bytes(b)) => a.cmp(b),
+            (DType::Date(a), DType::Date(b)) => a.cmp(b),
+            (DType::Uuid(a), DType::Uuid(b)) => a.cmp(b),
+            (DType::Decimal(a), DType::Decimal(b)) => a.cmp(b),
+            (DType::Enum(a), DType::Enum(b)) => a.cmp(b),
+            (DType::List(a), DType::List(b)) => a.cmp(b),
+            (DType::Json(a), DType::Json(b)) => a.cmp(b),
+            _ if self.rank() != other.rank() => self.rank().cmp(&other.rank()),
+            (DType::Float(a), _) => other.as_decimal().map_or(Ordering::Equal, |b| cmp_decimal_float(b, a.get()).reverse()),
+            (_, DType::Float(b)) => self.as_decimal().map_or(Ordering::Equal, |a| cmp_decimal_float(a, b.get())),
+            // Timestamps, including two of them, compare by instant alone so that equality
+            // with `DateTime` stays transitive
+            _ => match (self.as_decimal(), other.as_decimal()) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => self.as_instant().cmp(&other.as_instant()),
+            },
+        }
+    }
+}
+
+impl PartialOrd for DType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for DType {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DType {}
+
+impl<const N: usize> From<VarChar<N>> for DType {
+    fn from(value: VarChar<N>) -> Self {
+        DType::Str(Str::new(value.value))
+    }
+}
+
+impl<const N: usize> From<Char<N>> for DType {
+    fn from(value: Char<N>) -> Self {
+        DType::Str(Str::new(value.value))
+    }
+}
+
+impl From<DateTime> for DType {
+    fn from(value: DateTime) -> Self {
+        DType::DateTime(value)
+    }
+}
+
+impl From<Bool> for DType {
+    fn from(value: Bool) -> Self {
+        DType::Bool(value)
+    }
+}
+
+impl From<BigInt> for DType {
+    fn from(value: BigInt) -> Self {
+        DType::BigInt(value)
+    }
+}
+
+impl From<Float> for DType {
+    fn from(value: Float) -> Self {
+        DType::Float(value)
+    }
+}
+
+impl From<Bytes> for DType {
+    fn from(value: Bytes) -> Self {
+        DType::Bytes(value)
+    }
+}
+
+impl From<Date> for DType {
+    fn from(value: Date) -> Self {
+        DType::Date(value)
+    }
+}
+
+impl From<Uuid> for DType {
+    fn from(value: Uuid) -> Self {
+        DType::Uuid(value)
+    }
+}
+
+impl From<Timestamp> for DType {
+    fn from(value: Timestamp) -> Self {
+        DType::Timestamp(value)
+    }
+}
+
+impl From<Json> for DType {
+    fn from(value: Json) -> Self {
+        DType::Json(value)
+    }
+}
+
+impl<const P: u32, const S: u32> From<Decimal<P, S>> for DType {
+    fn from(value: Decimal<P, S>) -> Self {
+        DType::Decimal(value.get())
+    }
+}
+
+impl<T: Into<DType>> From<List<T>> for DType {
+    fn from(value: List<T>) -> Self {
+        DType::List(value.items.into_iter().map(Into::into).collect())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Deref;
+    use chrono::TimeZone;
+    use super::*;
+
+    #[test]
+    fn test_int() {
+        let num = 5;
+        let byte_array_be = [0u8, 0u8, 0u8, 5u8];
+        let byte_array_le = [5u8, 0u8, 0u8, 0u8];
+
+        let obj = Int::new(num);
+
+        assert_eq!(obj.get(), num);
+
+        if cfg!(target_endian = "big") {
+            assert_eq!(Int::deserialize(&byte_array_be).unwrap(), num);
+            assert_eq!(Int::from_(&byte_array_be).unwrap().get(), num);
+            assert_eq!(obj.serialize().deref(), byte_array_be);
+        } else {
+            assert_eq!(Int::deserialize(&byte_array_le).unwrap(), num);
+            assert_eq!(Int::from_(&byte_array_le).unwrap().get(), num);
+            assert_eq!(obj.serialize().deref(), byte_array_le);
+        }
+    }
+
+    #[test]
+    fn test_str() {
+        let text: String = "Amour Plastique".into();
+        let byte_array = [65, 109, 111, 117, 114, 32, 80, 108, 97, 115, 116, 105, 113, 117, 101];
+
+        let obj = Str::new(text.clone());
+        assert_eq!(obj.get(), text.clone());
+        assert_eq!(obj.serialize().deref(), byte_array.clone());
+        assert_eq!(Str::deserialize(&byte_array).unwrap(), text.clone());
+        assert_eq!(Str::from_(&byte_array).unwrap().get(), text.clone());
+    }
+
+    #[test]
+    fn test_datetime() {
+        let datetime = chrono::NaiveDateTime::from_timestamp(60, 1024);
+        let byte_array_be = [0, 0, 0, 13, 248, 71, 92, 0];
+        let byte_array_le = [0, 92, 71, 248, 13, 0, 0, 0];
+
+        let obj = DateTime::new(datetime);
+
+        assert_eq!(obj.get(), datetime);
+
+        if cfg!(target_endian = "big") {
+            assert_eq!(DateTime::deserialize(&byte_array_be).unwrap(), datetime);
+            assert_eq!(DateTime::from_(&byte_array_be).unwrap().get(), datetime);
+            assert_eq!(obj.serialize().deref(), byte_array_be);
+        } else {
+            assert_eq!(DateTime::deserialize(&byte_array_le).unwrap(), datetime);
+            assert_eq!(DateTime::from_(&byte_array_le).unwrap().get(), datetime);
+            assert_eq!(obj.serialize().deref(), byte_array_le);
+        }
+
+        // Only what fits into nanoseconds in an i64 can be stored
+        let first = DateTime::parse("1677-09-21T00:12:43.145224192").unwrap();
+        assert_eq!(first.serialize().deref(), i64::MIN.to_ne_bytes());
+        assert_eq!(DateTime::parse("2262-04-11T23:47:16.854775807").unwrap().serialize().deref(), i64::MAX.to_ne_bytes());
+        assert!(matches!(DateTime::parse("2300-01-01T00:00:00"), Err(Error {kind: ErrorKind::OutOfRange, ..})));
+        assert!(matches!(DateTime::parse("1677-09-21T00:12:43.145224191"), Err(Error {kind: ErrorKind::OutOfRange, ..})));
+        let late = DateTime::new(chrono::NaiveDate::from_ymd(2300, 1, 1).and_hms(0, 0, 0));
+        assert!(matches!(late.validate(), Err(Error {kind: ErrorKind::OutOfRange, ..})));
+        assert!(DateTime::try_new(late.get()).is_err());
+
+        // Timestamp stores seconds, so the same instants are fine there
+        let late = Timestamp::parse("2300-01-01T00:00:00Z").unwrap();
+        assert!(late.validate().is_ok());
+        assert_eq!(Timestamp::from_(&late.serialize()).unwrap(), late);
+        let last = Timestamp::new(chrono::DateTime::from_utc(chrono::naive::MAX_DATETIME, chrono::Utc));
+        assert_eq!(Timestamp::from_(&last.serialize()).unwrap(), last);
+    }
+
+    #[test]
+    fn test_bool() {
+        let obj = Bool::new(true);
+
+        assert!(obj.get());
+        assert_eq!(obj.serialize().deref(), [1u8]);
+        assert!(Bool::deserialize(&[1u8]).unwrap());
+        assert!(!Bool::deserialize(&[0u8]).unwrap());
+        assert!(!Bool::from_(&Bool::new(false).serialize()).unwrap().get());
+    }
+
+    #[test]
+    fn test_bigint() {
+        let num = 5_000_000_000i64;
+        let byte_array_be = [0u8, 0, 0, 1, 42, 5, 242, 0];
+        let byte_array_le = [0u8, 242, 5, 42, 1, 0, 0, 0];
+
+        let obj = BigInt::new(num);
+
+        assert_eq!(obj.get(), num);
+
+        if cfg!(target_endian = "big") {
+            assert_eq!(BigInt::deserialize(&byte_array_be).unwrap(), num);
+            assert_eq!(BigInt::from_(&byte_array_be).unwrap().get(), num);
+            assert_eq!(obj.serialize().deref(), byte_array_be);
+        } else {
+            assert_eq!(BigInt::deserialize(&byte_array_le).unwrap(), num);
+            assert_eq!(BigInt::from_(&byte_array_le).unwrap().get(), num);
+            assert_eq!(obj.serialize().deref(), byte_array_le);
+        }
+    }
+
+    #[test]
+    fn test_float() {
+        let num = 1.5f64;
+        let byte_array_be = [63u8, 248, 0, 0, 0, 0, 0, 0];
+        let byte_array_le = [0u8, 0, 0, 0, 0, 0, 248, 63];
+
+        let obj = Float::new(num);
+
+        assert_eq!(obj.get(), num);
+        assert_eq!(Float::new(f64::NAN), Float::new(f64::NAN));
+
+        if cfg!(target_endian = "big") {
+            assert_eq!(Float::deserialize(&byte_array_be).unwrap(), num);
+            assert_eq!(Float::from_(&byte_array_be).unwrap().get(), num);
+            assert_eq!(obj.serialize().deref(), byte_array_be);
+        } else {
+            assert_eq!(Float::deserialize(&byte_array_le).unwrap(), num);
+            assert_eq!(Float::from_(&byte_array_le).unwrap().get(), num);
+            assert_eq!(obj.serialize().deref(), byte_array_le);
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        let data = vec![0u8, 255, 16, 32];
+
+        let obj = Bytes::new(data.clone());
+        assert_eq!(obj.get(), data.clone());
+        assert_eq!(obj.serialize().deref(), data.as_slice());
+        assert_eq!(Bytes::deserialize(&data).unwrap(), data.clone());
+        assert_eq!(Bytes::from_(&data).unwrap().get(), data.clone());
+        assert_eq!(obj.to_string(), "00ff1020");
+    }
+
+    #[test]
+    fn test_date() {
+        let date = chrono::NaiveDate::from_ymd(2022, 2, 24);
+        // 738210 days since 0001-01-01
+        let byte_array_be = [0u8, 11, 67, 162];
+        let byte_array_le = [162u8, 67, 11, 0];
+
+        let obj = Date::new(date);
+
+        assert_eq!(obj.get(), date);
+
+        if cfg!(target_endian = "big") {
+            assert_eq!(Date::deserialize(&byte_array_be).unwrap(), date);
+            assert_eq!(Date::from_(&byte_array_be).unwrap().get(), date);
+            assert_eq!(obj.serialize().deref(), byte_array_be);
+        } else {
+            assert_eq!(Date::deserialize(&byte_array_le).unwrap(), date);
+            assert_eq!(Date::from_(&byte_array_le).unwrap().get(), date);
+            assert_eq!(obj.serialize().deref(), byte_array_le);
+        }
+    }
+
+    #[test]
+    fn test_uuid() {
+        let uuid = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let byte_array = [
+            103, 229, 80, 68, 16, 177, 66, 111, 146, 71, 187, 104, 14, 95, 224, 200
+        ];
+
+        let obj = Uuid::new(uuid);
+        assert_eq!(obj.get(), uuid);
+        assert_eq!(obj.serialize().deref(), byte_array);
+        assert_eq!(Uuid::deserialize(&byte_array).unwrap(), uuid);
+        assert_eq!(Uuid::from_(&byte_array).unwrap().get(), uuid);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(Int::from_(&[1u8, 2, 3]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(Str::from_(&[0xffu8, 0xfe]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(Bool::from_(&[2u8]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(DateTime::from_(&[]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(Uuid::from_(&[0u8; 15]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(
+            Date::from_(&i32::MAX.to_ne_bytes()),
+            Err(Error {kind: ErrorKind::Decode, ..})
+        ));
+    }
+
+    #[test]
+    fn test_decimal() {
+        let value = DecimalValue::new(12345, 2);
+        let byte_array_be = [0u8, 0, 48, 57];
+        let byte_array_le = [57u8, 48, 0, 0];
+
+        let obj = Decimal::<5, 2>::new(value);
+
+        assert_eq!(obj.get(), value);
+        assert_eq!(obj.to_string(), "123.45");
+        assert_eq!(Decimal::<12, 4>::new(value).serialize().len(), 8);
+        assert_eq!(Decimal::<20, 4>::new(value).serialize().len(), 16);
+
+        if cfg!(target_endian = "big") {
+            assert_eq!(Decimal::<5, 2>::deserialize(&byte_array_be).unwrap(), value);
+            assert_eq!(Decimal::<5, 2>::from_(&byte_array_be).unwrap().get(), value);
+            assert_eq!(obj.serialize().deref(), byte_array_be);
+        } else {
+            assert_eq!(Decimal::<5, 2>::deserialize(&byte_array_le).unwrap(), value);
+            assert_eq!(Decimal::<5, 2>::from_(&byte_array_le).unwrap().get(), value);
+            assert_eq!(obj.serialize().deref(), byte_array_le);
+        }
+
+        // Rescaled on construction, rejected when digits would be lost
+        assert_eq!(Decimal::<5, 3>::new(DecimalValue::new(15, 1)).get().mantissa(), 1500);
+        assert!(Decimal::<4, 2>::try_new(value).is_err());
+        assert!(Decimal::<5, 1>::try_new(value).is_err());
+        assert!(Decimal::<5, 2>::from_(&100000i32.to_ne_bytes()).is_err());
+
+        // Kept by `new` so that callers can't panic, but never valid
+        let overflowing = Decimal::<4, 2>::new(value);
+        assert_eq!(overflowing.get(), value);
+        assert!(matches!(overflowing.validate(), Err(Error {kind: ErrorKind::OutOfRange, ..})));
+        assert!(Decimal::<5, 1>::new(value).validate().is_err());
+        assert!(List::<Decimal<4, 2>>::new(vec![DecimalValue::new(1, 0), value]).validate().is_err());
+    }
+
+    #[test]
+    fn test_decimal_arithmetic() {
+        let price = DecimalValue::new(999, 2);
+        let royalty = DecimalValue::new(15, 3);
+
+        assert_eq!(price.checked_add(&royalty).unwrap().to_string(), "10.005");
+        assert_eq!(price.checked_sub(&royalty).unwrap().to_string(), "9.975");
+        assert_eq!(price.checked_mul(&royalty).unwrap().to_string(), "0.14985");
+        assert_eq!(price.checked_mul(&royalty).unwrap().round(2).to_string(), "0.15");
+        assert_eq!(price.checked_neg().unwrap().to_string(), "-9.99");
+        assert_eq!(DecimalValue::new(-5, 3).to_string(), "-0.005");
+        assert_eq!(DecimalValue::checked_sum(vec![price, price, price]).unwrap().to_string(), "29.97");
+
+        let max = DecimalValue::new(i128::MAX, 0);
+        assert_eq!(DecimalValue::checked_sum(vec![max, DecimalValue::new(1, 0)]), None);
+        assert_eq!(max.checked_mul(&DecimalValue::new(2, 0)), None);
+        assert_eq!(DecimalValue::new(i128::MIN, 0).checked_neg(), None);
+
+        assert_eq!(DecimalValue::new(150, 2), DecimalValue::new(15, 1));
+        assert!(DecimalValue::new(1, 0) > DecimalValue::new(99, 2));
+        assert!(DecimalValue::new(-1, 0) < DecimalValue::new(-99, 2));
+
+        // Scales too far apart to align in an i128
+        let tiny: DecimalValue = format!("0.{}5", "0".repeat(42)).parse().unwrap();
+        assert_eq!(tiny.scale(), 43);
+        assert!("0".parse::<DecimalValue>().unwrap() < tiny);
+        assert!(DecimalValue::new(-1, 43) < DecimalValue::new(0, 0));
+        assert!(DecimalValue::new(1, 0) > tiny);
+        assert!(DecimalValue::new(-1, 0) < DecimalValue::new(-5, 43));
+        assert_eq!(DecimalValue::new(5, 0), DecimalValue::new(5 * 10i128.pow(37), 37));
+        assert!(DecimalValue::new(i128::MAX, 0) > DecimalValue::new(i128::MAX, 50));
+        let one_and_a_half = DecimalValue::new(15 * 10i128.pow(37), 38);
+        assert!(DecimalValue::new(9, 0) > one_and_a_half);
+        assert!(DecimalValue::new(-9, 0) < one_and_a_half.checked_neg().unwrap());
+        assert!(DecimalValue::new(2, 0) > one_and_a_half && DecimalValue::new(1, 0) < one_and_a_half);
+        assert!(DecimalValue::new(9, 0).rescale(38).is_none());
+        assert_eq!(DecimalValue::new(0, 0), DecimalValue::new(0, 60));
+        assert!(DecimalValue::new(i128::MAX, 0) > DecimalValue::new(1, 5));
+        assert!(price.checked_mul(&DecimalValue::new(i128::MAX, 0)).is_none());
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let offset = chrono::FixedOffset::east(2 * 3600);
+        let local = offset.ymd(2022, 2, 24).and_hms_nano(5, 0, 0, 1024);
+        let obj = Timestamp::with_offset(local);
+
+        assert_eq!(obj.get(), chrono::Utc.ymd(2022, 2, 24).and_hms_nano(3, 0, 0, 1024));
+        assert_eq!(obj.offset(), Some(offset));
+        assert_eq!(obj.local(), local);
+        assert_eq!(obj.to_string(), "2022-02-24T05:00:00.000001024+02:00");
+
+        let raw = obj.serialize();
+        assert_eq!(raw.len(), 16);
+        assert_eq!(Timestamp::deserialize(&raw).unwrap(), obj.get());
+        assert_eq!(Timestamp::from_(&raw).unwrap(), obj);
+
+        // Without an offset the instant is reported in UTC
+        let utc = Timestamp::new(obj.get());
+        assert_eq!(utc.offset(), None);
+        assert_eq!(utc.to_string(), "2022-02-24T03:00:00.000001024Z");
+        assert_eq!(Timestamp::from_(&utc.serialize()).unwrap(), utc);
+
+        // Dates far outside of the `DateTime` range survive a round trip
+        let ancient = Timestamp::from_naive_utc(chrono::NaiveDate::from_ymd(-9000, 1, 1).and_hms(0, 0, 0));
+        assert_eq!(Timestamp::from_(&ancient.serialize()).unwrap(), ancient);
+
+        let datetime = DateTime::new(chrono::NaiveDateTime::from_timestamp(60, 1024));
+        assert_eq!(Timestamp::from(datetime.clone()).naive_utc(), datetime.get());
+    }
+
+    #[test]
+    fn test_list() {
+        let tags = vec![String::from("indie"), String::from("rock")];
+
+        let obj = List::<Str>::new(tags.clone());
+        assert_eq!(obj.get(), tags);
+        assert_eq!(obj.len(), 2);
+        assert_eq!(obj.to_string(), "[indie, rock]");
+
+        let raw = obj.serialize();
+        assert_eq!(raw.len(), 4 + (4 + 5) + (4 + 4));
+        assert_eq!(raw[..4], 2u32.to_ne_bytes());
+        assert_eq!(List::<Str>::deserialize(&raw).unwrap(), tags);
+        assert_eq!(List::<Str>::from_(&raw).unwrap(), obj);
+
+        let empty = List::<Int>::new(vec![]);
+        assert!(List::<Int>::from_(&empty.serialize()).unwrap().is_empty());
+
+        assert!(matches!(List::<Str>::from_(&raw[..raw.len() - 1]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(List::<Str>::from_(&raw[..2]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(List::<Int>::from_(&[255, 255, 255, 255]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert!(matches!(List::<Int>::from_(&[255, 255, 255, 255, 0, 0, 0, 0]), Err(Error {kind: ErrorKind::Decode, ..})));
+        assert_eq!(
+            DType::from(List::<Int>::new(vec![1, 2])),
+            DType::List(vec![DType::Int(Int::new(1)), DType::Int(Int::new(2))])
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let document = serde_json::json!({"stats": {"plays": 42, "skips": [1, 2]}});
+        let text = br#"{"stats":{"plays":42,"skips":[1,2]}}"#;
+
+        let obj = Json::new(document.clone());
+        assert_eq!(obj.get(), document);
+        assert_eq!(obj.serialize().deref(), text);
+        assert_eq!(Json::deserialize(text).unwrap(), document);
+        assert_eq!(Json::from_(text).unwrap().get(), document);
+        assert!(matches!(Json::from_(b"{\"stats\":"), Err(Error {kind: ErrorKind::Decode, ..})));
+
+        let plays = JsonPath::parse("$.stats.plays").unwrap();
+        assert_eq!(obj.path(&plays), Some(&serde_json::json!(42)));
+        assert_eq!(obj.path(&JsonPath::parse("$.stats.skips[1]").unwrap()), Some(&serde_json::json!(2)));
+        assert_eq!(obj.path(&JsonPath::parse("$[\"stats\"].plays").unwrap()), Some(&serde_json::json!(42)));
+        assert_eq!(obj.path(&JsonPath::parse("$").unwrap()), Some(&document));
+        assert_eq!(obj.path(&JsonPath::parse("$.stats.likes").unwrap()), None);
+
+        assert!(JsonPath::parse("stats.plays").is_err());
+        assert!(JsonPath::parse("$.stats[").is_err());
+        assert!(JsonPath::parse("$..plays").is_err());
+    }
+
+    #[test]
+    fn test_bounded_strings() {
+        let name = String::from("Kasabian");
+
+        let obj = VarChar::<8>::new(name.clone());
+        assert_eq!(obj.get(), name);
+        assert_eq!(obj.serialize().deref(), name.as_bytes());
+        assert_eq!(VarChar::<8>::from_(name.as_bytes()).unwrap(), obj);
+        assert!(VarChar::<8>::try_new(name.clone()).is_ok());
+        // Length is counted in characters rather than bytes
+        assert!(VarChar::<6>::try_new("Привіт".into()).is_ok());
+
+        let error = VarChar::<7>::try_new(name.clone()).unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::Validation));
+        assert_eq!(error.message, "value is 8 characters long, VarChar<7> allows at most 7");
+        assert!(VarChar::<7>::new(name.clone()).validate().is_err());
+        assert!(VarChar::<7>::from_(name.as_bytes()).is_err());
+
+        let code = Char::<3>::new("en".into());
+        assert_eq!(code.get(), "en ");
+        assert_eq!(Char::<3>::from_(b"en ").unwrap(), code);
+        assert!(Char::<1>::try_new("en".into()).is_err());
+        // Short values are padded by `new` but rejected when read back unpadded
+        assert!(Char::<3>::try_new("e".into()).is_ok());
+        let error = Char::<3>::from_(b"en").unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::Validation));
+        assert_eq!(error.message, "value is 2 characters long, Char<3> requires exactly 3");
+
+        let tags = List::<VarChar<4>>::new(vec!["rock".into(), "dubstep".into()]);
+        assert_eq!(tags.validate().unwrap_err().message, "List item 1: value is 7 characters long, VarChar<4> allows at most 4");
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        fn round_trip<V, T: DataType<V>>(text: &str) -> String {
+            T::parse(text).unwrap_or_else(|e| panic!("{}", e.message)).format()
+        }
+
+        assert_eq!(round_trip::<_, Int>("-42"), "-42");
+        assert_eq!(round_trip::<_, Str>("Club foot"), "Club foot");
+        assert_eq!(round_trip::<_, Bool>("true"), "true");
+        assert_eq!(round_trip::<_, BigInt>("5000000000"), "5000000000");
+        assert_eq!(round_trip::<_, Float>("0.1"), "0.1");
+        assert_eq!(round_trip::<_, Bytes>("00ff1020"), "00ff1020");
+        assert_eq!(round_trip::<_, Date>("2022-02-24"), "2022-02-24");
+        assert_eq!(round_trip::<_, DateTime>("2022-02-24T05:00:00"), "2022-02-24T05:00:00");
+        assert_eq!(round_trip::<_, DateTime>("2022-02-24 05:00:00.5"), "2022-02-24T05:00:00.500");
+        assert_eq!(round_trip::<_, Timestamp>("2022-02-24T05:00:00+02:00"), "2022-02-24T05:00:00+02:00");
+        assert_eq!(round_trip::<_, Timestamp>("2022-02-24T05:00:00Z"), "2022-02-24T05:00:00Z");
+        assert_eq!(
+            round_trip::<_, Uuid>("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        );
+        assert_eq!(round_trip::<_, Decimal<5, 2>>("12.3"), "12.30");
+        assert_eq!(round_trip::<_, Decimal<5, 2>>("-0.05"), "-0.05");
+        assert_eq!(round_trip::<_, List<Int>>(r#"["1", "2"]"#), r#"["1","2"]"#);
+        assert_eq!(round_trip::<_, Json>(r#"{"plays": 42}"#), r#"{"plays":42}"#);
+
+        assert!(matches!(Int::parse("4.2"), Err(Error {kind: ErrorKind::Parse, ..})));
+        assert!(Bool::parse("yes").is_err());
+        assert!(Bytes::parse("0").is_err());
+        assert!(Bytes::parse("zz").is_err());
+        assert!(Date::parse("24.02.2022").is_err());
+        assert!(Decimal::<5, 2>::parse("1.234").is_err());
+        assert!(Decimal::<5, 2>::parse("1.2.3").is_err());
+        assert!(VarChar::<3>::parse("Kasabian").is_err());
+        assert!(List::<Int>::parse(r#"["1", "x"]"#).is_err());
+
+        assert_eq!("0.50".parse::<DecimalValue>().unwrap(), DecimalValue::new(5, 1));
+        assert_eq!(".5".parse::<DecimalValue>().unwrap(), DecimalValue::new(5, 1));
+        assert_eq!(DType::from(Date::parse("2022-02-24").unwrap()).format(), "2022-02-24");
+        assert_eq!(DType::from(List::<Str>::new(vec!["a".into()])).format(), r#"["a"]"#);
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(Int::new(-1) < Int::new(1));
+        assert!(Str::new("Abyss".into()) < Str::new("Club foot".into()));
+        assert!(Float::new(f64::INFINITY) < Float::new(f64::NAN));
+        assert!(Float::new(-0.0) < Float::new(0.0));
+        assert!(Date::new(chrono::NaiveDate::from_ymd(2022, 2, 24)) > Date::new(chrono::NaiveDate::from_ymd(2014, 2, 20)));
+        assert!(List::<Int>::new(vec![1, 2]) < List::<Int>::new(vec![1, 3]));
+        assert!(Json::new(serde_json::json!(null)) < Json::new(serde_json::json!(false)));
+        assert!(Json::new(serde_json::json!(2)) < Json::new(serde_json::json!(10.5)));
+        assert!(Json::new(serde_json::json!({"a": 1})) < Json::new(serde_json::json!({"a": 2})));
+
+        // JSON numbers compare exactly, and equality agrees with the ordering
+        let json = |value: serde_json::Value| Json::new(value);
+        assert!(json(serde_json::json!(9007199254740993u64)) > json(serde_json::json!(9007199254740992.0)));
+        assert_eq!(json(serde_json::json!(9007199254740992.0)), json(serde_json::json!(9007199254740992i64)));
+        assert!(json(serde_json::json!(u64::MAX)) > json(serde_json::json!(i64::MAX)));
+        assert!(json(serde_json::json!(-1)) < json(serde_json::json!(-0.5)));
+        assert_eq!(json(serde_json::json!(1)), json(serde_json::json!(1.0)));
+        assert_eq!(json(serde_json::json!([0, {"a": -0.0}])), json(serde_json::json!([0.0, {"a": 0}])));
+        assert_eq!(json(serde_json::json!(1)).cmp(&json(serde_json::json!(1.0))), Ordering::Equal);
+
+        let offset = chrono::FixedOffset::east(3600);
+        let earlier = Timestamp::with_offset(offset.ymd(2022, 2, 24).and_hms(5, 0, 0));
+        let later = Timestamp::new(chrono::Utc.ymd(2022, 2, 24).and_hms(4, 30, 0));
+        assert!(earlier < later);
+
+        let mut values = vec![
+            DType::Str(Str::new("a".into())),
+            DType::Float(Float::new(2.5)),
+            DType::Int(Int::new(3)),
+            DType::Decimal(DecimalValue::new(-15, 1)),
+            DType::Bool(Bool::new(true)),
+            DType::BigInt(BigInt::new(2)),
+        ];
+        values.sort();
+        assert_eq!(values, vec![
+            DType::Bool(Bool::new(true)),
+            DType::Decimal(DecimalValue::new(-15, 1)),
+            DType::BigInt(BigInt::new(2)),
+            DType::Float(Float::new(2.5)),
+            DType::Int(Int::new(3)),
+            DType::Str(Str::new("a".into())),
+        ]);
+
+        assert_eq!(DType::Int(Int::new(2)), DType::BigInt(BigInt::new(2)));
+        assert_eq!(DType::Int(Int::new(2)), DType::Decimal(DecimalValue::new(200, 2)));
+        assert_eq!(DType::Float(Float::new(2.0)), DType::BigInt(BigInt::new(2)));
+        assert!(DType::BigInt(BigInt::new(i64::MAX)) > DType::Int(Int::new(i32::MAX)));
+        assert_ne!(DType::Int(Int::new(2)), DType::Str(Str::new("2".into())));
+
+        let date = DType::Date(Date::new(chrono::NaiveDate::from_ymd(2022, 2, 24)));
+        let midnight = DType::DateTime(DateTime::new(chrono::NaiveDate::from_ymd(2022, 2, 24).and_hms(0, 0, 0)));
+        assert_eq!(date, midnight);
+        assert!(DType::Timestamp(later) > midnight);
+
+        // Numbers compare exactly, even where a float can't represent the integer
+        let float = DType::Float(Float::new(9007199254740992.0));
+        let above = DType::BigInt(BigInt::new(9007199254740993));
+        assert!(above > float);
+        assert_eq!(float, DType::BigInt(BigInt::new(9007199254740992)));
+        assert!(DType::Decimal(DecimalValue::new(1, 1)) > DType::Float(Float::new(0.1 - f64::EPSILON)));
+        assert!(DType::Decimal(DecimalValue::new(1, 1)) < DType::Float(Float::new(0.1)));
+        assert_eq!(DType::Decimal(DecimalValue::new(-25, 1)), DType::Float(Float::new(-2.5)));
+        assert_eq!(DType::Float(Float::new(-0.0)), DType::Float(Float::new(0.0)));
+        assert!(DType::Float(Float::new(f64::NAN)) > DType::BigInt(BigInt::new(i64::MAX)));
+        assert!(DType::Float(Float::new(f64::NEG_INFINITY)) < DType::Int(Int::new(i32::MIN)));
+
+        // Timestamps with the same instant are equal whatever their offset, like the DateTime
+        let instant = chrono::Utc.ymd(2022, 2, 24).and_hms(4, 0, 0);
+        let local = DType::Timestamp(Timestamp::with_offset(chrono::FixedOffset::east(7200).from_utc_datetime(&instant.naive_utc())));
+        let utc = DType::Timestamp(Timestamp::new(instant));
+        assert_eq!(local, DType::DateTime(DateTime::new(instant.naive_utc())));
+        assert_eq!(local, utc);
+        assert_eq!(values.iter().max(), Some(&DType::Str(Str::new("a".into()))));
     }
 
 }
\ No newline at end of file
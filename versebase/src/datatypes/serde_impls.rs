@@ -0,0 +1,85 @@
+//! `Serialize`/`Deserialize` for datatypes, enabled by the `serde` feature.
+//!
+//! Numbers, strings, booleans and JSON are represented as themselves; other types use the
+//! text from `DataType::format` and are read back with `DataType::parse`, so constraints
+//! such as `VarChar` lengths or `Decimal` precision are checked on deserialization.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::*;
+
+
+macro_rules! as_value {
+    ($ty:ty, $value:ty) => {
+        impl Serialize for $ty {
+            fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+                self.value.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <$value>::deserialize(deserializer).map(Self::new)
+            }
+        }
+    };
+}
+
+macro_rules! as_text {
+    ($ty:ty $(; $($generics:tt)*)?) => {
+        impl<$($($generics)*)?> Serialize for $ty {
+            fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+                serializer.serialize_str(&self.format())
+            }
+        }
+
+        impl<'de, $($($generics)*)?> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let text = String::deserialize(deserializer)?;
+                Self::parse(&text).map_err(|e| D::Error::custom(e.message))
+            }
+        }
+    };
+}
+
+as_value!(Int, i32);
+as_value!(Str, String);
+as_value!(Bool, bool);
+as_value!(BigInt, i64);
+as_value!(Float, f64);
+as_value!(Json, serde_json::Value);
+
+as_text!(VarChar<N>; const N: usize);
+as_text!(Char<N>; const N: usize);
+as_text!(DateTime);
+as_text!(Bytes);
+as_text!(Date);
+as_text!(Uuid);
+as_text!(Decimal<P, S>; const P: u32, const S: u32);
+as_text!(Timestamp);
+
+impl Serialize for DecimalValue {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DecimalValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(|e: Error| D::Error::custom(e.message))
+    }
+}
+
+impl<T: Serialize> Serialize for List<T> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.items.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for List<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {items: Vec::deserialize(deserializer)?})
+    }
+}
@@ -1,7 +0,0 @@
-struct Database {
-    state_file: String,
-}
-
-impl Database {
-
-}
\ No newline at end of file
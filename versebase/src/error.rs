@@ -2,8 +2,7 @@
 // https://learning-rust.github.io/docs/e7.custom_error_types.html
 
 
-use std::fs::File;
-use std::io::{self, Read};
+use std::io;
 use std::num;
 use std::fmt;
 
@@ -14,15 +13,30 @@ pub struct Error {
     pub message: String,
 }
 
+impl Error {
+    /// `Validation` error about the value of `column`, as returned by validation hooks,
+    /// see `#[versebase(validate = ...)]`.
+    pub fn validation(column: &str, message: impl fmt::Display) -> Self {
+        Error {
+            kind: ErrorKind::Validation,
+            message: format!("column \"{}\": {}", column, message),
+        }
+    }
+}
+
 pub enum ErrorKind {
     // 3rd-party errors wrappers
     Io,
     Parse,
+    Decode,
 
     // 1-st party errors
     FilePointerCorrupt,
     AlreadyExists,
     NotFound,
+    OutOfRange,
+    Validation,
+    SchemaMismatch,
 }
 
 impl ErrorKind {
@@ -31,9 +45,13 @@ impl ErrorKind {
         match *self {
             Io => "io error",
             Parse => "parsing error",
+            Decode => "decoding error",
             FilePointerCorrupt => "file pointer is corrupt",
             AlreadyExists => "already exists",
-            NotFound => "record not found"
+            NotFound => "record not found",
+            OutOfRange => "value out of range",
+            Validation => "validation error",
+            SchemaMismatch => "schema mismatch",
         }
     }
 }
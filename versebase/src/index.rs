@@ -1,13 +1,10 @@
 use std::path::Path;
-use std::marker::PhantomData;
-use std::collections::{HashMap, BTreeMap};
-use std::{fs, u64};
+use std::collections::BTreeMap;
 use std::io;
-use std::fs::{File, OpenOptions};
-use std::io::{BufReader, Read, Seek, SeekFrom, Write};
-use std::io::ErrorKind::AlreadyExists;
-use std::ops::Deref;
-use rand::distributions::uniform::SampleBorrow;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use super::datatypes::DType;
 
 
 pub struct TableIndex {
@@ -19,34 +16,24 @@ pub struct TableIndex {
 
 impl TableIndex {
     pub fn new(filepath: Box<Path>) -> Result<Self, io::Error> {
-        let file = match Self::init_file(&filepath) {
-            Ok(f) => f,
-            Err(e) => return Err(e),
-        };
+        let file = Self::init_file(&filepath)?;
 
         let mut instance = TableIndex {
             filepath,
             tree: BTreeMap::new(),
             file,
         };
-        instance.load();
+        instance.load()?;
 
         Ok(instance)
     }
 
-    fn init_file(path: &Box<Path>) -> Result<File, io::Error> {
-        let file = match OpenOptions::new()
+    fn init_file(path: &Path) -> Result<File, io::Error> {
+        OpenOptions::new()
             .read(true)
             .append(true)
-            .write(true)
             .create(true)
-            .truncate(false)
-            .open(&path) {
-            Ok(f) => f,
-            Err(e) => return Result::Err(e),
-        };
-
-        Result::Ok(file)
+            .open(path)
     }
 
     pub fn load(&mut self) -> Result<(), std::io::Error> {
@@ -58,8 +45,8 @@ impl TableIndex {
             self.file.read_exact(&mut id_buf).unwrap();
             self.file.read_exact(&mut pos_buf).unwrap();
 
-            let id = i32::from_ne_bytes(id_buf.try_into().unwrap());
-            let pos = u64::from_ne_bytes(pos_buf.try_into().unwrap());
+            let id = i32::from_ne_bytes(id_buf);
+            let pos = u64::from_ne_bytes(pos_buf);
 
             self.tree.insert(id, pos);
         }
@@ -70,8 +57,8 @@ impl TableIndex {
     fn dump(&mut self) -> Result<(), std::io::Error> {
         self.file.set_len(0)?;
         for (id, pos) in &self.tree {
-            self.file.write(&id.to_ne_bytes())?;
-            self.file.write(&pos.to_ne_bytes())?;
+            self.file.write_all(&id.to_ne_bytes())?;
+            self.file.write_all(&pos.to_ne_bytes())?;
         }
         self.file.sync_data().unwrap();
 
@@ -83,10 +70,12 @@ impl TableIndex {
     }
 
     pub fn get(&self, id: i32) -> Option<u64> {
-        match self.tree.get(&id) {
-            Some(e) => Some(*e),
-            None => None
-        }
+        self.tree.get(&id).copied()
+    }
+
+    /// Ids of every row, in ascending order.
+    pub fn ids(&self) -> impl DoubleEndedIterator<Item = i32> + '_ {
+        self.tree.keys().copied()
     }
 
     pub fn set(&mut self, id: i32, pos: u64) {
@@ -94,12 +83,27 @@ impl TableIndex {
         self.dump().unwrap();
     }
 
+    /// Sets the positions of several rows, writing the index file once.
+    pub fn set_many<I: IntoIterator<Item = (i32, u64)>>(&mut self, entries: I) {
+        self.tree.extend(entries);
+        self.dump().unwrap();
+    }
+
     pub fn delete(&mut self, id: i32) -> Option<u64> {
         let result = self.tree.remove(&id);
         self.dump().unwrap();
         result
     }
 
+    /// Moves rows stored at or after `from` back by `by` bytes, after the bytes
+    /// before them were erased.
+    pub fn shift(&mut self, from: u64, by: u64) {
+        for pos in self.tree.values_mut().filter(|pos| **pos >= from) {
+            *pos -= by;
+        }
+        self.dump().unwrap();
+    }
+
     pub fn clear(&mut self) {
         self.tree.clear();
         self.dump().unwrap();
@@ -108,7 +112,109 @@ impl TableIndex {
 
 impl Drop for TableIndex {
     fn drop(&mut self) {
-        self.dump();
+        let _ = self.dump();
+    }
+}
+
+
+/// Highest id a table has ever had, persisted so that `Table::insert_auto` never hands out
+/// the id of a deleted row again.
+pub struct Sequence {
+    pub filepath: Box<Path>,
+    last: i32,
+}
+
+impl Sequence {
+    /// Loads the sequence from `filepath`, starting from 0 if the file doesn't exist.
+    pub fn new(filepath: Box<Path>) -> Result<Self, io::Error> {
+        let last = match fs::read(&filepath) {
+            Ok(raw) => match raw.try_into() {
+                Ok(raw) => i32::from_ne_bytes(raw),
+                Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "sequence file is corrupt")),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        Ok(Self {filepath, last})
+    }
+
+    pub fn last(&self) -> i32 {
+        self.last
+    }
+
+    /// The id following the last one, `None` once `i32::MAX` was reached.
+    pub fn next(&self) -> Option<i32> {
+        self.last.checked_add(1)
+    }
+
+    /// Records that `id` is taken; smaller ids leave the sequence as it is.
+    pub fn advance(&mut self, id: i32) -> Result<(), io::Error> {
+        if id > self.last {
+            self.last = id;
+            fs::write(&self.filepath, id.to_ne_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Declaration of a secondary index, see `TableSchema::indexes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSpec {
+    pub field: &'static str,
+    /// No two rows may have the same value in the column.
+    pub unique: bool,
+}
+
+
+/// In-memory index of a column's values, mapping each value to the ids of rows having it.
+/// Built when a table is opened and kept in sync on every write.
+pub struct SecondaryIndex {
+    pub spec: IndexSpec,
+    tree: BTreeMap<DType, Vec<i32>>,
+}
+
+impl SecondaryIndex {
+    pub fn new(spec: IndexSpec) -> Self {
+        Self {spec, tree: BTreeMap::new()}
+    }
+
+    /// Ids of rows where the column is equal to `value`.
+    pub fn get(&self, value: &DType) -> &[i32] {
+        self.tree.get(value).map_or(&[], |ids| ids.as_slice())
+    }
+
+    /// Ids of every row, ordered by the column; rows with equal values are ordered by
+    /// `position` either way.
+    pub fn ordered_ids<P: Ord>(&self, descending: bool, position: impl Fn(i32) -> P) -> Vec<i32> {
+        let buckets: Box<dyn Iterator<Item = &Vec<i32>>> = match descending {
+            false => Box::new(self.tree.values()),
+            true => Box::new(self.tree.values().rev()),
+        };
+        buckets
+            .flat_map(|ids| {
+                let mut ids = ids.clone();
+                ids.sort_by_key(|id| position(*id));
+                ids
+            })
+            .collect()
+    }
+
+    pub fn insert(&mut self, value: DType, id: i32) {
+        self.tree.entry(value).or_default().push(id);
+    }
+
+    pub fn remove(&mut self, value: &DType, id: i32) {
+        if let Some(ids) = self.tree.get_mut(value) {
+            ids.retain(|found| *found != id);
+            if ids.is_empty() {
+                self.tree.remove(value);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tree.clear();
     }
 }
 
@@ -1,7 +1,27 @@
 #![feature(seek_stream_len)]
 
 pub mod error;
-pub mod db;
 pub mod index;
 pub mod table;
-pub mod datatypes;
\ No newline at end of file
+pub mod schema;
+pub mod query;
+pub mod datatypes;
+
+#[cfg(feature = "serde")]
+pub use serde;
+
+/// Expands to the given items only when the `serde` feature is enabled; lets
+/// `#[derive(TableSchema)]` emit serde impls without knowing how versebase was built.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_impls {
+    ($($item:item)*) => { $($item)* };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_impls {
+    ($($item:item)*) => {};
+}
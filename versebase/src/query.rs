@@ -0,0 +1,408 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::datatypes::{DType, DataType, Json, JsonPath, List};
+use super::error::{Error, ErrorKind};
+use super::table::TableSchema;
+
+
+/// Value a filter or a projection refers to: a whole column, or a value inside a `Json`
+/// column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    Field(String),
+    JsonPath(String, JsonPath),
+}
+
+impl Column {
+    /// Refers to `path` (e.g. `$.stats.plays`) inside the `Json` column `field`.
+    pub fn json(field: &str, path: &str) -> Result<Self, Error> {
+        Ok(Column::JsonPath(field.to_string(), JsonPath::parse(path)?))
+    }
+
+    pub fn field(&self) -> &str {
+        match self {
+            Column::Field(field) | Column::JsonPath(field, _) => field,
+        }
+    }
+
+    /// Values found inside a `Json` column are returned as `DType::Json`.
+    pub fn resolve<S: TableSchema>(&self, row: &S) -> Option<DType> {
+        match self {
+            Column::Field(field) => row.get(field.clone()),
+            Column::JsonPath(field, path) => match row.get(field.clone())? {
+                DType::Json(json) => json.path(path).cloned().map(|value| DType::Json(Json::new(value))),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl From<&str> for Column {
+    fn from(field: &str) -> Self {
+        Column::Field(field.to_string())
+    }
+}
+
+impl From<String> for Column {
+    fn from(field: String) -> Self {
+        Column::Field(field)
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Column::Field(field) => write!(f, "{}", field),
+            Column::JsonPath(field, path) => write!(f, "{}{}", field, path),
+        }
+    }
+}
+
+
+/// Condition on a row's columns, see `Table::select_where`.
+///
+/// Comparisons with a column the row doesn't have (such as a missing JSON path) are false,
+/// whichever the operator; only `IsNull` matches such rows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Column is equal to the value.
+    Eq(Column, DType),
+    /// Column is not equal to the value.
+    Ne(Column, DType),
+    /// Column is less than the value, see `DType` for how values of different types compare.
+    Lt(Column, DType),
+    /// Column is less than or equal to the value.
+    Le(Column, DType),
+    /// Column is greater than the value.
+    Gt(Column, DType),
+    /// Column is greater than or equal to the value.
+    Ge(Column, DType),
+    /// Column is equal to one of the values.
+    In(Column, Vec<DType>),
+    /// Column is between the two values, inclusive.
+    Between(Column, DType, DType),
+    /// `Str` column starts with the prefix.
+    StartsWith(Column, String),
+    /// `Str` column matches the SQL pattern, where `%` stands for any run of characters,
+    /// `_` for a single one and `\` escapes the next character.
+    Like(Column, String),
+    /// Column is missing or is a JSON `null`.
+    IsNull(Column),
+    /// `List` column has an item equal to the value.
+    Contains(Column, DType),
+    /// Every filter matches.
+    And(Vec<Filter>),
+    /// At least one filter matches.
+    Or(Vec<Filter>),
+    /// The filter doesn't match.
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    pub fn matches<S: TableSchema>(&self, row: &S) -> bool {
+        match self {
+            Filter::Eq(column, value) => column.resolve(row).as_ref() == Some(value),
+            Filter::Ne(column, value) => column.resolve(row).is_some_and(|found| found != *value),
+            Filter::Lt(column, value) => column.resolve(row).is_some_and(|found| found < *value),
+            Filter::Le(column, value) => column.resolve(row).is_some_and(|found| found <= *value),
+            Filter::Gt(column, value) => column.resolve(row).is_some_and(|found| found > *value),
+            Filter::Ge(column, value) => column.resolve(row).is_some_and(|found| found >= *value),
+            Filter::In(column, values) => column.resolve(row).is_some_and(|found| values.contains(&found)),
+            Filter::Between(column, low, high) => column
+                .resolve(row)
+                .is_some_and(|found| *low <= found && found <= *high),
+            Filter::StartsWith(column, prefix) => match column.resolve(row) {
+                Some(DType::Str(value)) => value.get().starts_with(prefix.as_str()),
+                _ => false,
+            },
+            Filter::Like(column, pattern) => match column.resolve(row) {
+                Some(DType::Str(value)) => like(&value.get(), pattern),
+                _ => false,
+            },
+            Filter::IsNull(column) => match column.resolve(row) {
+                Some(DType::Json(json)) => json.get().is_null(),
+                found => found.is_none(),
+            },
+            Filter::Contains(column, value) => match column.resolve(row) {
+                Some(DType::List(items)) => items.contains(value),
+                Some(DType::Json(json)) => match (json.get(), value) {
+                    (serde_json::Value::Array(items), DType::Json(value)) => items.contains(&value.get()),
+                    _ => false,
+                },
+                _ => false,
+            },
+            Filter::And(filters) => filters.iter().all(|filter| filter.matches(row)),
+            Filter::Or(filters) => filters.iter().any(|filter| filter.matches(row)),
+            Filter::Not(filter) => !filter.matches(row),
+        }
+    }
+
+    /// Fails if the filter refers to a column `S` doesn't have.
+    pub fn check_columns<S: TableSchema>(&self) -> Result<(), Error> {
+        match self {
+            Filter::Eq(column, _)
+            | Filter::Ne(column, _)
+            | Filter::Lt(column, _)
+            | Filter::Le(column, _)
+            | Filter::Gt(column, _)
+            | Filter::Ge(column, _)
+            | Filter::In(column, _)
+            | Filter::Between(column, _, _)
+            | Filter::StartsWith(column, _)
+            | Filter::Like(column, _)
+            | Filter::IsNull(column)
+            | Filter::Contains(column, _) => check_column::<S>(column.field()),
+            Filter::And(filters) | Filter::Or(filters) => filters
+                .iter()
+                .try_for_each(|filter| filter.check_columns::<S>()),
+            Filter::Not(filter) => filter.check_columns::<S>(),
+        }
+    }
+}
+
+/// SQL `LIKE`, see `Filter::Like`.
+fn like(text: &str, pattern: &str) -> bool {
+    enum Token {
+        AnyRun,
+        AnyChar,
+        Char(char),
+    }
+
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '%' => Token::AnyRun,
+            '_' => Token::AnyChar,
+            '\\' => Token::Char(chars.next().unwrap_or('\\')),
+            c => Token::Char(c),
+        });
+    }
+
+    // Greedy matching, going back to the last `%` on a mismatch
+    let text: Vec<char> = text.chars().collect();
+    let (mut t, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match tokens.get(p) {
+            Some(Token::AnyRun) => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(Token::AnyChar) => {
+                t += 1;
+                p += 1;
+            }
+            Some(Token::Char(c)) if *c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            _ => match backtrack {
+                Some((run, start)) => {
+                    backtrack = Some((run, start + 1));
+                    p = run + 1;
+                    t = start + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[p..].iter().all(|token| matches!(token, Token::AnyRun))
+}
+
+pub(crate) fn check_column<S: TableSchema>(field: &str) -> Result<(), Error> {
+    match S::fields().iter().any(|known| known == field) {
+        true => Ok(()),
+        false => Err(Error {
+            kind: ErrorKind::NotFound,
+            message: format!("unknown column \"{}\"", field),
+        }),
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+
+/// Rows to read from a table: those matching `filter`, sorted by `order_by` (in the order
+/// they're stored otherwise), skipping `offset` of them and returning at most `limit`.
+/// See `Table::select_by`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Select {
+    pub filter: Filter,
+    /// Columns to sort by, the first one taking precedence; rows missing a column come
+    /// first in ascending order. Ties keep the order rows are stored in.
+    pub order_by: Vec<(Column, Order)>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl Default for Select {
+    /// Every row.
+    fn default() -> Self {
+        Self {filter: Filter::And(Vec::new()), order_by: Vec::new(), limit: None, offset: 0}
+    }
+}
+
+impl Select {
+    /// Rows must also match `filter`.
+    pub fn and(&mut self, filter: Filter) {
+        match &mut self.filter {
+            Filter::And(filters) => filters.push(filter),
+            current => {
+                let current = std::mem::replace(current, Filter::And(Vec::new()));
+                self.filter = Filter::And(vec![current, filter]);
+            }
+        }
+    }
+
+    /// Values `row` is sorted by.
+    pub(crate) fn sort_key<S: TableSchema>(&self, row: &S) -> Vec<SortKey> {
+        self.order_by
+            .iter()
+            .map(|(column, order)| SortKey {value: column.resolve(row), order: *order})
+            .collect()
+    }
+}
+
+
+/// Value of an `order_by` column, ordered so that the rows to return first are the least.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SortKey {
+    value: Option<DType>,
+    order: Order,
+}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.order {
+            Order::Asc => self.value.cmp(&other.value),
+            Order::Desc => other.value.cmp(&self.value),
+        }
+    }
+}
+
+
+/// Query on the table of `Schema`, built by `#[derive(TableSchema)]` as e.g. `SongsQuery`
+/// (see `Songs::query()`) and run with `Table::execute`.
+pub trait QueryBuilder: Sized {
+    type Schema: TableSchema;
+    /// Columns of `Schema`, e.g. `SongsColumn`.
+    type Column: Into<Column>;
+
+    fn select_mut(&mut self) -> &mut Select;
+    fn into_select(self) -> Select;
+
+    /// Adds a condition every returned row must satisfy.
+    fn filter(mut self, filter: Filter) -> Self {
+        self.select_mut().and(filter);
+        self
+    }
+
+    /// Sorts rows by `column`, after the columns given before.
+    fn order_by(mut self, column: Self::Column, order: Order) -> Self {
+        self.select_mut().order_by.push((column.into(), order));
+        self
+    }
+
+    fn limit(mut self, limit: usize) -> Self {
+        self.select_mut().limit = Some(limit);
+        self
+    }
+
+    fn offset(mut self, offset: usize) -> Self {
+        self.select_mut().offset = offset;
+        self
+    }
+
+    fn into_filter(self) -> Filter {
+        self.into_select().filter
+    }
+}
+
+
+/// Condition on a single column of a query, see `QueryBuilder`; `T` is the column's datatype,
+/// so only values and comparisons valid for the column compile.
+pub struct ColumnFilter<Q, T> {
+    query: Q,
+    column: Column,
+    datatype: PhantomData<T>,
+}
+
+impl<Q: QueryBuilder, T> ColumnFilter<Q, T> where DType: From<T> {
+    pub fn new(query: Q, column: Column) -> Self {
+        Self {query, column, datatype: PhantomData}
+    }
+
+    /// Values that don't fit the column, like a too long string, are compared as they are.
+    fn value<V>(value: impl Into<V>) -> DType where T: DataType<V> {
+        DType::from(T::new(value.into()))
+    }
+
+    pub fn eq<V>(self, value: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Eq(self.column, Self::value(value)))
+    }
+
+    pub fn lt<V>(self, value: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Lt(self.column, Self::value(value)))
+    }
+
+    pub fn le<V>(self, value: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Le(self.column, Self::value(value)))
+    }
+
+    pub fn gt<V>(self, value: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Gt(self.column, Self::value(value)))
+    }
+
+    pub fn ge<V>(self, value: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Ge(self.column, Self::value(value)))
+    }
+
+    pub fn ne<V>(self, value: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Ne(self.column, Self::value(value)))
+    }
+
+    pub fn is_in<V, I>(self, values: I) -> Q where T: DataType<V>, I: IntoIterator, I::Item: Into<V> {
+        let values = values.into_iter().map(Self::value).collect();
+        self.query.filter(Filter::In(self.column, values))
+    }
+
+    /// `low <= column <= high`
+    pub fn between<V>(self, low: impl Into<V>, high: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Between(self.column, Self::value(low), Self::value(high)))
+    }
+
+    pub fn is_null(self) -> Q {
+        self.query.filter(Filter::IsNull(self.column))
+    }
+}
+
+impl<Q: QueryBuilder, T: DataType<String>> ColumnFilter<Q, T> {
+    pub fn starts_with(self, prefix: &str) -> Q {
+        self.query.filter(Filter::StartsWith(self.column, prefix.to_string()))
+    }
+
+    /// See `Filter::Like` for the pattern syntax.
+    pub fn like(self, pattern: &str) -> Q {
+        self.query.filter(Filter::Like(self.column, pattern.to_string()))
+    }
+}
+
+impl<Q: QueryBuilder, T> ColumnFilter<Q, List<T>> where DType: From<T> {
+    /// The list has an item equal to `item`.
+    pub fn contains<V>(self, item: impl Into<V>) -> Q where T: DataType<V> {
+        self.query.filter(Filter::Contains(self.column, DType::from(T::new(item.into()))))
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use super::datatypes::DataKind;
+
+
+/// Description of a table's columns, returned by `TableSchema::schema`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SchemaInfo {
+    pub table: &'static str,
+    /// Columns in the order they are declared and stored.
+    pub columns: Vec<ColumnInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ColumnInfo {
+    pub name: &'static str,
+    /// Position of the column in the struct and in stored rows, starting at 0.
+    pub ordinal: usize,
+    pub kind: DataKind,
+    /// May hold a null, see `DataType::nullable`.
+    pub nullable: bool,
+    pub primary_key: bool,
+    /// Has a secondary index, see `#[versebase(index)]`.
+    pub indexed: bool,
+    pub unique: bool,
+}
+
+impl SchemaInfo {
+    pub fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    pub fn primary_key(&self) -> &ColumnInfo {
+        self.columns.iter().find(|column| column.primary_key).expect("schema has no primary key")
+    }
+
+    /// 64-bit FNV-1a hash of the columns' names and types in their order, i.e. of what
+    /// decides how rows are stored; keys and indexes can change without changing it.
+    /// Kept in the header of table files to refuse opening them with another schema.
+    pub fn fingerprint(&self) -> u64 {
+        let layout: Vec<String> = self.columns
+            .iter()
+            .map(|column| format!("{} {}", column.name, column.kind))
+            .collect();
+        layout.join(",").bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+}
+
+/// Formats the schema as e.g.
+///
+/// ```text
+/// accounts (
+///     user_id Int primary key,
+///     email Str unique
+/// )
+/// ```
+impl fmt::Display for SchemaInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} (", self.table)?;
+        for (i, column) in self.columns.iter().enumerate() {
+            write!(f, "    {}", column)?;
+            writeln!(f, "{}", if i + 1 == self.columns.len() { "" } else { "," })?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for ColumnInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.kind)?;
+        if self.nullable {
+            write!(f, " null")?;
+        }
+        if self.primary_key {
+            write!(f, " primary key")?;
+        }
+        if self.unique {
+            write!(f, " unique")?;
+        } else if self.indexed {
+            write!(f, " index")?;
+        }
+        Ok(())
+    }
+}
@@ -1,70 +1,164 @@
 use std::path::Path;
 use std::marker::PhantomData;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
 use std::fmt;
-use std::fs::{self, File, OpenOptions};
-use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
-use std::io::ErrorKind::AlreadyExists;
-use std::any::Any;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 
-use super::error::{self, Error, ErrorKind};
-use super::index::{TableIndex};
-use super::datatypes::{DataType, DType};
+use super::error::{Error, ErrorKind};
+use super::index::{IndexSpec, SecondaryIndex, Sequence, TableIndex};
+use super::datatypes::DType;
+use super::schema::SchemaInfo;
+use super::query::{check_column, Column, Filter, Order, QueryBuilder, Select, SortKey};
 
 const DELIMITER_SIZE: usize = 8;
 const FIELDS_DELIMITER: [u8; DELIMITER_SIZE] = [255, 0, 255, 0, 255, 0, 255, 0];
 const ROWS_DELIMITER: [u8; DELIMITER_SIZE] = [0, 127, 0, 255, 0, 127, 0, 255];
+const HEADER_MAGIC: [u8; 8] = *b"vrsbtbl1";
+/// Magic bytes followed by the schema fingerprint; rows start right after it.
+const HEADER_SIZE: u64 = 16;
 
 pub trait TableSchema: fmt::Display {
-    fn from_(raw: Vec<(String, Box<[u8]>)>) -> Self;
+    fn from_(raw: Vec<(String, Box<[u8]>)>) -> Result<Self, Error>
+        where Self: Sized;
     fn fields() -> Vec<String>;
-    fn print_info();
+    /// Column names, datatypes, keys and indexes.
+    fn schema() -> SchemaInfo
+        where Self: Sized;
+    fn print_info()
+        where Self: Sized
+    {
+        println!("{}", Self::schema());
+    }
 
+    /// `#[versebase(table = "...")]`, or the struct name in snake case.
+    fn table_name() -> &'static str;
+    /// Column holding the row id: the `#[versebase(primary_key)]` field, or `id`.
+    fn primary_key() -> &'static str;
+    /// Columns marked with `#[versebase(index)]` or `#[versebase(unique)]`.
+    fn indexes() -> Vec<IndexSpec>;
+
+    /// Builds a row from the textual form of every column, see `DataType::parse`.
+    fn from_strings(values: HashMap<String, String>) -> Result<Self, Error>
+        where Self: Sized;
+    /// Parses `text` as a value of the column `field`.
+    fn parse_value(field: &str, text: &str) -> Result<DType, Error>
+        where Self: Sized;
+
+    /// Checks every column's constraints (see `DataType::validate`), then runs the
+    /// `#[versebase(validate = ...)]` hooks of the fields and of the struct.
+    fn validate(&self) -> Result<(), Error>;
     fn get(&self, field: String) -> Option<DType>;
+    /// Sets the `#[versebase(auto_now)]` columns, and the `#[versebase(auto_now_add)]` ones
+    /// if the row is being inserted; called by `Table` before a row is written.
+    fn fill_auto(&mut self, insert: bool) {
+        let _ = insert;
+    }
     fn get_id(&self) -> i32;
+    fn set_id(&mut self, id: i32);
     fn to_map(&self) -> HashMap<String, DType>;
     fn serialize_to_vec(&self) -> Vec<(String, Box<[u8]>)>;
     fn serialize_to_map(&self) -> HashMap<String, Box<[u8]>>;
 }
 
 
+/// Gives access to the table of `S`; implemented by a database struct for each of its tables
+/// so that generated relation accessors (`#[versebase(references = ...)]`) can reach them.
+pub trait HasTable<S: TableSchema> {
+    fn table(&mut self) -> &mut Table<S>;
+}
+
+impl<S: TableSchema> HasTable<S> for Table<S> {
+    fn table(&mut self) -> &mut Table<S> {
+        self
+    }
+}
+
+
+/// Partial update of a row, generated by `#[derive(TableSchema)]` as e.g. `UsersPatch`;
+/// applied with `Table::patch`.
+pub trait SchemaPatch {
+    type Schema: TableSchema;
+
+    /// Columns the patch sets.
+    fn columns(&self) -> Vec<&'static str>;
+    /// Overwrites the set columns of `row`.
+    fn apply(self, row: &mut Self::Schema);
+}
+
+
 struct TableFile<S: TableSchema> {
-    pub filepath: Box<Path>,
+    pub name: String,
     pub schema: PhantomData<S>,
     file: File,
 }
 // File structure looks like
+// (HEADER_MAGIC)[fingerprint of S::schema()]
 // [row1_field1](FIELDS_DELIMITER)[row1_field2](FIELDS_DELIMITER)[row1_field3](ROWS_DELIMITER)
 // [row2_field1](FIELDS_DELIMITER)[row2_field2](FIELDS_DELIMITER)[row2_field3](ROWS_DELIMITER)
 // [row3_field1](FIELDS_DELIMITER)[row3_field2](FIELDS_DELIMITER)[row3_field3](ROWS_DELIMITER)
 
 impl<S: TableSchema> TableFile<S> {
-    pub fn new(filepath: Box<Path>) -> Result<Self, Error> {
-        let file = match Self::init_file(&filepath) {
-            Ok(f) => f,
-            Err(e) => return Err(e.into()),
-        };
+    pub fn new(name: String, filepath: Box<Path>) -> Result<Self, Error> {
+        let file = Self::init_file(&filepath)?;
 
-        Ok(TableFile {
-            filepath,
+        let mut table_file = TableFile {
+            name,
             schema: PhantomData,
             file,
-        })
+        };
+        table_file.check_header()?;
+        Ok(table_file)
     }
 
-    fn init_file(path: &Box<Path>) -> Result<File, io::Error> {
-        let file = match OpenOptions::new()
+    /// Fails if the rows were written with a schema of another layout, see
+    /// `SchemaInfo::fingerprint`. New files, and files from before headers were written,
+    /// get a header.
+    fn check_header(&mut self) -> Result<(), Error> {
+        let fingerprint = S::schema().fingerprint();
+        let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+        self.file.seek(SeekFrom::Start(0))?;
+        (&mut self.file).take(HEADER_SIZE).read_to_end(&mut header)?;
+
+        if header.len() == HEADER_SIZE as usize && header[..8] == HEADER_MAGIC {
+            let stored = u64::from_ne_bytes(header[8..].try_into().unwrap());
+            if stored != fingerprint {
+                return Err(Error {
+                    kind: ErrorKind::SchemaMismatch,
+                    message: format!(
+                        "table \"{}\" was written with another schema (fingerprint {:016x}, expected {:016x})",
+                        self.name, stored, fingerprint,
+                    ),
+                });
+            }
+            return Ok(());
+        }
+
+        let mut rows = Vec::new();
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_end(&mut rows)?;
+        let mut content = Vec::with_capacity(HEADER_SIZE as usize + rows.len());
+        content.extend_from_slice(&HEADER_MAGIC);
+        content.extend_from_slice(&fingerprint.to_ne_bytes());
+        content.extend_from_slice(&rows);
+        self.file.set_len(0)?;
+        self.file.write_all(&content)?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// Moves to the first row.
+    pub fn rewind(&mut self) -> Result<(), Error> {
+        self.seek(HEADER_SIZE as i64)
+    }
+
+    fn init_file(path: &Path) -> Result<File, io::Error> {
+        OpenOptions::new()
             .read(true)
             .append(true)
-            .write(true)
             .create(true)
-            .truncate(false)
-            .open(&path) {
-            Ok(f) => f,
-            Err(e) => return Result::Err(e),
-        };
-
-        Result::Ok(file)
+            .open(path)
     }
 
     pub fn seek(&mut self, pos: i64) -> Result<(), Error> {
@@ -72,10 +166,8 @@ impl<S: TableSchema> TableFile<S> {
             pos if pos >= 0 => SeekFrom::Start(pos as u64),
             pos => SeekFrom::End(pos + 1)
         };
-        return match self.file.seek(seek) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e.into())
-        };
+        self.file.seek(seek)?;
+        Ok(())
     }
 
     pub fn position(&mut self) -> u64 {
@@ -83,8 +175,7 @@ impl<S: TableSchema> TableFile<S> {
     }
 
     fn at_beginning(&mut self) -> bool {
-        let pos = self.position() as i64;
-        pos == 0
+        self.position() == HEADER_SIZE
     }
 
     fn at_end(&mut self) -> Result<bool, Error> {
@@ -120,7 +211,7 @@ impl<S: TableSchema> TableFile<S> {
         while !self.at_end()? {
             let mut b = [0u8; 1];
             self.file.read_exact(&mut b)?;
-            &buf.push(b[0]);
+            buf.push(b[0]);
 
             let possible_delimiter = match buf.len() as i32 - DELIMITER_SIZE as i32 {
                 e if e >= 0 => &buf[e as usize..],
@@ -131,9 +222,9 @@ impl<S: TableSchema> TableFile<S> {
             let at_field_end = possible_delimiter == FIELDS_DELIMITER;
 
             if at_row_end || at_field_end {
-                let field = Box::<[u8]>::from(buf[..&buf.len() - DELIMITER_SIZE].to_vec());
+                let field = Box::<[u8]>::from(&buf[..buf.len() - DELIMITER_SIZE]);
                 fields_raw.push(field);
-                &buf.clear();
+                buf.clear();
             }
             if at_row_end {
                 break;
@@ -143,54 +234,89 @@ impl<S: TableSchema> TableFile<S> {
 
         let fields_names = S::fields();
 
-        assert_eq!(&fields_raw.len(), &fields_names.len());
+        if fields_raw.len() != fields_names.len() {
+            return Err(self.decode_error(
+                pos_begin as u64,
+                format!("expected {} columns, got {}", fields_names.len(), fields_raw.len()),
+            ));
+        }
 
         let fields: Vec<(String, Box<[u8]>)> = fields_names
-            .iter()
-            .zip(fields_raw.iter())
-            .map(|e| (e.0.clone(), e.1.clone()))
+            .into_iter()
+            .zip(fields_raw)
             .collect()
             ;
 
-        Ok(Some((S::from_(fields), pos_begin as u64, pos_end)))
+        match S::from_(fields) {
+            Ok(row) => Ok(Some((row, pos_begin as u64, pos_end))),
+            Err(e) => Err(self.decode_error(pos_begin as u64, e.message)),
+        }
     }
 
-    pub fn write_row(&mut self, row: &S) -> Result<(u64, u64), Error> {
-        match self.seek(-1) {
-            Err(e) => return Err(e.into()),
-            _ => ()
-        };
-        let data = row.serialize_to_vec();
+    fn decode_error(&self, offset: u64, message: String) -> Error {
+        Error {
+            kind: ErrorKind::Decode,
+            message: format!("table \"{}\", row at offset {}: {}", self.name, offset, message),
+        }
+    }
 
-        let begin_pos = self.position();
-        for i in 0..data.len() {
-            self.file.write_all(&data[i].1).unwrap();
+    fn encode_row(row: &S, buf: &mut Vec<u8>) {
+        let data = row.serialize_to_vec();
+        for (i, (_, value)) in data.iter().enumerate() {
+            buf.extend_from_slice(value);
             if i != data.len() - 1 {
-                self.file.write_all(&FIELDS_DELIMITER).unwrap();
+                buf.extend_from_slice(&FIELDS_DELIMITER);
             }
         }
-        self.file.write_all(&ROWS_DELIMITER).unwrap();
+        buf.extend_from_slice(&ROWS_DELIMITER);
+    }
+
+    pub fn write_row(&mut self, row: &S) -> Result<(u64, u64), Error> {
+        self.seek(-1)?;
+        let mut buf = Vec::new();
+        Self::encode_row(row, &mut buf);
+
+        let begin_pos = self.position();
+        self.file.write_all(&buf)?;
 
         let end_pos = self.position();
-        return match self.file.sync_data() {
-            Ok(_) => Ok((begin_pos, end_pos)),
-            Err(e) => Err(e.into())
-        };
+        self.file.sync_data()?;
+        Ok((begin_pos, end_pos))
+    }
+
+    /// Appends `rows` with a single write, returning where each of them begins. Nothing is
+    /// left in the file if writing fails.
+    pub fn write_rows(&mut self, rows: &[S]) -> Result<Vec<u64>, Error> {
+        self.seek(-1)?;
+        let begin_pos = self.position();
+
+        let mut buf = Vec::new();
+        let mut positions = Vec::with_capacity(rows.len());
+        for row in rows {
+            positions.push(begin_pos + buf.len() as u64);
+            Self::encode_row(row, &mut buf);
+        }
+
+        if let Err(e) = self.file.write_all(&buf).and_then(|()| self.file.sync_data()) {
+            self.file.set_len(begin_pos)?;
+            return Err(e.into());
+        }
+        Ok(positions)
     }
 
     pub fn erase(&mut self, begin: u64, end: u64) -> Result<(), Error> {
         assert!(begin < end);
 
         // Save data after the "end" pointer
-        self.file.seek(SeekFrom::Start(end));
+        self.file.seek(SeekFrom::Start(end))?;
         let mut buf = Vec::<u8>::with_capacity((self.file.stream_len()? - end) as usize);
-        self.file.read_to_end(&mut buf);
+        self.file.read_to_end(&mut buf)?;
 
         // Crop file after "end"
-        self.file.set_len(begin);
-        self.file.seek(SeekFrom::End(0));
+        self.file.set_len(begin)?;
+        self.file.seek(SeekFrom::End(0))?;
         // Write all the saved subsequent data
-        self.file.write_all(&buf);
+        self.file.write_all(&buf)?;
 
         self.file.flush()?;
 
@@ -199,9 +325,21 @@ impl<S: TableSchema> TableFile<S> {
 }
 
 
+/// What `Table::upsert` or `Table::insert_or_ignore` did with a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteAction {
+    Inserted,
+    Replaced,
+    /// A row with the same id already existed.
+    Ignored,
+}
+
+
 pub struct Table<S: TableSchema> {
     pub name: String,
     pub index: Option<TableIndex>,
+    secondary_indexes: Vec<SecondaryIndex>,
+    sequence: Sequence,
     file: TableFile<S>,
     schema: PhantomData<S>,
 }
@@ -212,45 +350,64 @@ impl<S: TableSchema> Table<S> {
         filepath: Box<Path>,
         index: Option<TableIndex>,
     ) -> Result<Table<S>, Error> {
-        let file = match TableFile::<S>::new(filepath.clone()) {
-            Ok(f) => f,
-            Err(e) => return Err(e)
-        };
+        // Ids allocated by `insert_auto` are kept next to the rows, e.g. in `songs.seq`
+        let sequence_path = filepath.with_extension("seq");
+        let sequence_exists = sequence_path.exists();
+        let sequence = Sequence::new(Box::from(sequence_path.as_path()))?;
+        let file = TableFile::<S>::new(name.clone(), filepath)?;
 
         let mut table = Table {
             name,
             index,
+            secondary_indexes: S::indexes().into_iter().map(SecondaryIndex::new).collect(),
+            sequence,
             file,
             schema: PhantomData,
         };
-        table.refresh_indexes();
+        table.refresh_indexes()?;
+        if !sequence_exists {
+            // Start after the rows of tables created before sequences were kept
+            table.file.rewind()?;
+            let mut max_id = 0;
+            while let Some((row, _, _)) = table.file.read_row()? {
+                max_id = max_id.max(row.get_id());
+            }
+            table.sequence.advance(max_id)?;
+        }
 
         Ok(table)
     }
 
+    /// Opens the table `S::table_name()` in `dir`, keeping its rows in `<table name>.tbl`,
+    /// its primary key index in `<table name>.idx` and its id sequence in `<table name>.seq`.
+    pub fn open(dir: &Path) -> Result<Table<S>, Error> {
+        let name = S::table_name();
+        let index = TableIndex::new(Box::from(dir.join(format!("{}.idx", name)).as_path()))?;
+        Self::new(name.to_string(), Box::from(dir.join(format!("{}.tbl", name)).as_path()), Some(index))
+    }
+
     pub fn get(&mut self, id: i32) -> Result<S, Error> {
         match &mut self.index {
             Some(index) => {
-                return match index.get(id) {
+                match index.get(id) {
                     Some(pos) => {
-                        self.file.seek(pos as i64);
-                        match self.file.read_row() {
-                            Ok(Some((row, _, _))) => Ok(row),
-                            Ok(None) => Err(Error {
+                        self.file.seek(pos as i64)?;
+                        match self.file.read_row()? {
+                            Some((row, _, _)) => Ok(row),
+                            None => Err(Error {
                                 kind: ErrorKind::NotFound,
                                 message: "record with a given id doesn't exist".to_string()
                             }),
-                            Err(e) => Err(e)
                         }
                     }
                     None => Err(Error {
                         kind: ErrorKind::NotFound,
                         message: "record with a given id doesn't exist".to_string()
                     }),
-                };
+                }
             }
             None => {
-                self.file.seek(0)?;
+                self.file.rewind()?;
                 loop {
                     match self.file.read_row()? {
                         Some((row, _, _)) if row.get_id() == id => return Ok(row),
@@ -262,91 +419,428 @@ impl<S: TableSchema> Table<S> {
                     }
                 }
             }
-        };
+        }
     }
 
+    /// Returns rows where every given column is equal to its value; fails on unknown columns.
     pub fn select(&mut self, filter: HashMap<String, DType>) -> Result<Vec<S>, Error> {
-        self.file.seek(0)?;
+        for field in filter.keys() {
+            check_column::<S>(field)?;
+        }
+        self.scan(|row| filter.iter().all(|(field, value)| row.get(field.to_string()).as_ref() == Some(value)))
+    }
 
-        let mut result = Vec::<S>::new();
-        loop {
-            match self.file.read_row()? {
-                Some((row, _, _)) => {
-                    let mut is_valid = true;
-                    for (filter_field, filter_value) in filter.iter() {
-                        match &row.get(filter_field.to_string()) {
-                            Some(value) if value != filter_value => {
-                                is_valid = false;
-                                break;
-                            }
-                            _ => continue
-                        }
-                    }
-                    if is_valid {
-                        result.push(row);
+    /// Runs a query built with e.g. `Songs::query()`.
+    pub fn execute<Q: QueryBuilder<Schema = S>>(&mut self, query: Q) -> Result<Vec<S>, Error> {
+        self.select_by(&query.into_select())
+    }
+
+    /// Returns the rows described by `select`, see `Select`.
+    ///
+    /// Rows are read in the order of an index when the ordering is by a single indexed column
+    /// (or the primary key) and the filter can't use an index itself; otherwise with a limit,
+    /// only the best `offset + limit` rows are kept while reading.
+    pub fn select_by(&mut self, select: &Select) -> Result<Vec<S>, Error> {
+        select.filter.check_columns::<S>()?;
+        for (column, _) in &select.order_by {
+            check_column::<S>(column.field())?;
+        }
+        let limit = select.limit.unwrap_or(usize::MAX);
+
+        if select.order_by.is_empty() {
+            return window(self.select_iter(select.filter.clone()), select.offset, limit);
+        }
+        if let Some(ids) = self.ordered_ids(select) {
+            let cursor = Cursor {table: self, source: CursorSource::Ids(ids.into_iter()), filter: Some(select.filter.clone())};
+            return window(cursor, select.offset, limit);
+        }
+
+        let kept = select.offset.saturating_add(limit);
+        if kept == 0 {
+            return Ok(Vec::new());
+        }
+        let mut heap = BinaryHeap::new();
+        let mut rows = Vec::new();
+        for (seq, row) in self.select_iter(select.filter.clone()).enumerate() {
+            let row = row?;
+            let ranked = Ranked {key: select.sort_key(&row), seq, row};
+            match select.limit {
+                // Top-N: the greatest row is the one to drop once there are more than needed
+                Some(_) => {
+                    heap.push(ranked);
+                    if heap.len() > kept {
+                        heap.pop();
                     }
                 }
-                None => break
+                None => rows.push(ranked),
+            }
+        }
+        let rows = match select.limit {
+            Some(_) => heap.into_sorted_vec(),
+            None => {
+                rows.sort();
+                rows
             }
         };
+        Ok(rows.into_iter().skip(select.offset).take(limit).map(|ranked| ranked.row).collect())
+    }
+
+    /// Ids of every row in the order `select` asks for, if an index has them in that order
+    /// and the filter has no index to use instead.
+    fn ordered_ids(&self, select: &Select) -> Option<Vec<i32>> {
+        let (field, order) = match select.order_by.as_slice() {
+            [(Column::Field(field), order)] => (field, *order),
+            _ => return None,
+        };
+        let index = self.index.as_ref()?;
+        if self.indexed_ids(&select.filter).is_some() {
+            return None;
+        }
+        if field == S::primary_key() {
+            return Some(match order {
+                Order::Asc => index.ids().collect(),
+                Order::Desc => index.ids().rev().collect(),
+            });
+        }
+        self.secondary_indexes
+            .iter()
+            .find(|index| index.spec.field == field)
+            .map(|secondary| secondary.ordered_ids(order == Order::Desc, |id| index.get(id)))
+    }
+
+    /// Returns rows matching `filter`; fails if it refers to an unknown column.
+    pub fn select_where(&mut self, filter: &Filter) -> Result<Vec<S>, Error> {
+        self.select_iter(filter.clone()).collect()
+    }
+
+    /// Lazily reads every row of the table.
+    pub fn iter(&mut self) -> Cursor<'_, S> {
+        Cursor {table: self, source: CursorSource::Scan(HEADER_SIZE), filter: None}
+    }
+
+    /// Lazily reads the rows matching `filter`, like `select_where`; an unknown column is
+    /// reported by the first item.
+    pub fn select_iter(&mut self, filter: Filter) -> Cursor<'_, S> {
+        let source = match filter.check_columns::<S>() {
+            Err(e) => CursorSource::Failed(e),
+            Ok(()) => match (self.indexed_ids(&filter), &self.index) {
+                (Some(ids), Some(_)) => CursorSource::Ids(ids.into_iter()),
+                _ => CursorSource::Scan(HEADER_SIZE),
+            },
+        };
+        Cursor {table: self, source, filter: Some(filter)}
+    }
+
+    /// Ids of the only rows that can match `filter`, if a secondary index can tell.
+    fn indexed_ids(&self, filter: &Filter) -> Option<Vec<i32>> {
+        match filter {
+            Filter::Eq(Column::Field(field), value) => self.secondary_indexes
+                .iter()
+                .find(|index| index.spec.field == field)
+                .map(|index| self.storage_order(index.get(value).to_vec())),
+            Filter::In(Column::Field(field), values) => {
+                let index = self.secondary_indexes.iter().find(|index| index.spec.field == field)?;
+                let ids = values.iter().flat_map(|value| index.get(value).iter().copied()).collect();
+                Some(self.storage_order(ids))
+            }
+            Filter::And(filters) => filters.iter().find_map(|filter| self.indexed_ids(filter)),
+            // Only if every alternative is indexed
+            Filter::Or(filters) => {
+                let mut ids = Vec::new();
+                for filter in filters {
+                    ids.extend(self.indexed_ids(filter)?);
+                }
+                Some(self.storage_order(ids))
+            }
+            _ => None,
+        }
+    }
+
+    /// Sorts `ids` by where their rows are stored, so that reading them returns rows in
+    /// the same order as a scan, and drops duplicates.
+    fn storage_order(&self, mut ids: Vec<i32>) -> Vec<i32> {
+        let position = |id: &i32| self.index.as_ref().and_then(|index| index.get(*id));
+        ids.sort_unstable_by_key(|id| (position(id), *id));
+        ids.dedup();
+        ids
+    }
 
-        Ok(result)
+    /// Returns the values of `columns` for every row matching `filter`, `None` standing for
+    /// a missing column or JSON path.
+    pub fn project(&mut self, filter: &Filter, columns: &[Column]) -> Result<Vec<Vec<Option<DType>>>, Error> {
+        Ok(self.select_where(filter)?
+            .iter()
+            .map(|row| columns.iter().map(|column| column.resolve(row)).collect())
+            .collect())
+    }
+
+    /// Smallest value of `column` among rows matching `filter`, `None` if there is none.
+    pub fn min(&mut self, filter: &Filter, column: &Column) -> Result<Option<DType>, Error> {
+        Ok(self.project(filter, std::slice::from_ref(column))?
+            .into_iter()
+            .filter_map(|mut values| values.pop().flatten())
+            .min())
+    }
+
+    /// Largest value of `column` among rows matching `filter`, `None` if there is none.
+    pub fn max(&mut self, filter: &Filter, column: &Column) -> Result<Option<DType>, Error> {
+        Ok(self.project(filter, std::slice::from_ref(column))?
+            .into_iter()
+            .filter_map(|mut values| values.pop().flatten())
+            .max())
+    }
+
+    /// Reads the whole table, returning rows accepted by `predicate`.
+    fn scan<F: FnMut(&S) -> bool>(&mut self, mut predicate: F) -> Result<Vec<S>, Error> {
+        self.iter().filter(|row| row.as_ref().map_or(true, &mut predicate)).collect()
     }
 
     pub fn create(&mut self, row: S) -> Result<i32, Error> {
-        return match &mut self.index {
-            Some(index) => {
-                if index.exists(row.get_id()) {
+        if self.exists(row.get_id())? {
+            return Err(Error {
+                kind: ErrorKind::AlreadyExists,
+                message: "id already exists".to_string()
+            })
+        }
+        self.insert(row)
+    }
+
+    /// Inserts `row`, or replaces the row with the same id if there is one.
+    pub fn upsert(&mut self, row: S) -> Result<WriteAction, Error> {
+        match self.find(row.get_id())? {
+            Some((old, begin, end)) => self.replace(old, row, begin, end).map(|()| WriteAction::Replaced),
+            None => self.insert(row).map(|_| WriteAction::Inserted),
+        }
+    }
+
+    /// Inserts all of `rows` or, if any of them is invalid or conflicts with a stored row or
+    /// another row of the batch, none of them. Rows are written at once and indexes are
+    /// updated after, which is much faster than calling `create` for each row.
+    pub fn insert_many<I: IntoIterator<Item = S>>(&mut self, rows: I) -> Result<Vec<i32>, Error> {
+        let mut rows: Vec<S> = rows.into_iter().collect();
+        let mut ids = BTreeSet::new();
+        let mut unique_values = vec![BTreeSet::new(); self.secondary_indexes.len()];
+        // Without an index, read the stored ids once rather than once per row
+        let stored_ids: Option<BTreeSet<i32>> = match &self.index {
+            Some(_) => None,
+            None => Some(self.scan(|_| true)?.iter().map(|row| row.get_id()).collect()),
+        };
+        for row in &mut rows {
+            row.fill_auto(true);
+            row.validate()?;
+            let id = row.get_id();
+            let exists = match &stored_ids {
+                Some(stored_ids) => stored_ids.contains(&id),
+                None => self.exists(id)?,
+            };
+            if !ids.insert(id) || exists {
+                return Err(Error {
+                    kind: ErrorKind::AlreadyExists,
+                    message: format!("id {} already exists", id),
+                });
+            }
+            self.check_unique(row, id)?;
+            for (index, values) in self.secondary_indexes.iter().zip(&mut unique_values) {
+                let value = match row.get(index.spec.field.to_string()) {
+                    Some(value) if index.spec.unique => value,
+                    _ => continue,
+                };
+                if !values.insert(value.clone()) {
                     return Err(Error {
                         kind: ErrorKind::AlreadyExists,
-                        message: "id already exists".to_string()
-                    })
+                        message: format!("column \"{}\" is unique, {:?} occurs twice", index.spec.field, value.format()),
+                    });
                 }
-                let written_pos = self.file.write_row(&row).unwrap();
-                index.set(row.get_id(), written_pos.0);
-
-                Ok(row.get_id())
             }
-            None => {
-                let existing = self.get((&row).get_id());
-                match existing {
-                    Ok(_) => Err(Error {
-                        kind: ErrorKind::AlreadyExists,
-                        message: "id already exists".to_string()
-                    }),
-                    Err(Error {kind: ErrorKind::AlreadyExists, .. }) => {
-                        self.file.write_row(&row).unwrap();
-                        Ok((&row).get_id())
-                    },
-                    Err(e) => Err(e)
+        }
+
+        // Before writing, so that a failure can't leave rows behind that aren't indexed; at
+        // worst the ids are skipped
+        if let Some(&max_id) = ids.last() {
+            self.sequence.advance(max_id)?;
+        }
+        let positions = self.file.write_rows(&rows)?;
+        if let Some(index) = &mut self.index {
+            index.set_many(rows.iter().map(|row| row.get_id()).zip(positions));
+        }
+        for index in &mut self.secondary_indexes {
+            for row in &rows {
+                if let Some(value) = row.get(index.spec.field.to_string()) {
+                    index.insert(value, row.get_id());
                 }
             }
         }
+
+        Ok(rows.iter().map(|row| row.get_id()).collect())
+    }
+
+    /// Inserts `row` unless a row with the same id exists, leaving that one untouched.
+    /// Other conflicts, such as a taken `unique` value, are still errors.
+    pub fn insert_or_ignore(&mut self, row: S) -> Result<WriteAction, Error> {
+        match self.exists(row.get_id())? {
+            true => Ok(WriteAction::Ignored),
+            false => self.insert(row).map(|_| WriteAction::Inserted),
+        }
+    }
+
+    /// Writes a row whose id isn't taken.
+    fn insert(&mut self, mut row: S) -> Result<i32, Error> {
+        row.fill_auto(true);
+        row.validate()?;
+        self.check_unique(&row, row.get_id())?;
+
+        self.sequence.advance(row.get_id())?;
+        let written_pos = self.file.write_row(&row)?;
+        if let Some(index) = &mut self.index {
+            index.set(row.get_id(), written_pos.0);
+        }
+        for index in &mut self.secondary_indexes {
+            if let Some(value) = row.get(index.spec.field.to_string()) {
+                index.insert(value, row.get_id());
+            }
+        }
+
+        Ok(row.get_id())
+    }
+
+    fn exists(&mut self, id: i32) -> Result<bool, Error> {
+        match &self.index {
+            Some(index) => Ok(index.exists(id)),
+            None => Ok(self.find(id)?.is_some()),
+        }
+    }
+
+    /// Inserts `row` under the next id of the table's sequence, returning the id. Ids are
+    /// never reused, even after the row holding one is deleted.
+    pub fn insert_auto(&mut self, mut row: S) -> Result<i32, Error> {
+        let id = self.sequence.next().ok_or_else(|| Error {
+            kind: ErrorKind::OutOfRange,
+            message: format!("table \"{}\" has run out of ids", self.name),
+        })?;
+        row.set_id(id);
+        self.create(row)
+    }
+
+    /// Fails if a row other than `row` (or the row `replaced` it's written over) already has
+    /// one of its `unique` values.
+    fn check_unique(&self, row: &S, replaced: i32) -> Result<(), Error> {
+        for index in self.secondary_indexes.iter().filter(|index| index.spec.unique) {
+            let value = match row.get(index.spec.field.to_string()) {
+                Some(value) => value,
+                None => continue,
+            };
+            if index.get(&value).iter().any(|id| *id != row.get_id() && *id != replaced) {
+                return Err(Error {
+                    kind: ErrorKind::AlreadyExists,
+                    message: format!("column \"{}\" is unique, {:?} already exists", index.spec.field, value.format()),
+                });
+            }
+        }
+        Ok(())
     }
 
+    /// Replaces the row having the same id as `row`.
     pub fn update(&mut self, row: S) -> Result<(), Error> {
-        let pos = match self.find(row.get_id())? {
+        let (old, begin, end) = match self.find(row.get_id())? {
             Some(e) => e,
-            None =>  return Err(Error {kind: ErrorKind::NotFound, message: "record not found".to_string()}),
+            None => return Err(Error {kind: ErrorKind::NotFound, message: "record not found".to_string()}),
+        };
+        self.replace(old, row, begin, end)
+    }
+
+    /// Writes `row` over `old`, the row with the same id stored between `begin` and `end`.
+    fn replace(&mut self, old: S, mut row: S, begin: u64, end: u64) -> Result<(), Error> {
+        row.fill_auto(false);
+        row.validate()?;
+        self.check_unique(&row, row.get_id())?;
+        let old_values = self.indexed_values(&old);
+        self.rewrite(old.get_id(), old_values, &row, begin, end)
+    }
+
+    /// Sets the columns provided by `patch` (and `auto_now` ones) on the row `id`, returning
+    /// the updated row. Only the indexes of changed columns are updated.
+    pub fn patch<P: SchemaPatch<Schema = S>>(&mut self, id: i32, patch: P) -> Result<S, Error> {
+        let (mut row, begin, end) = match self.find(id)? {
+            Some(e) => e,
+            None => return Err(Error {kind: ErrorKind::NotFound, message: "record not found".to_string()}),
         };
-        return Ok(());
+        let old_values = self.indexed_values(&row);
+        patch.apply(&mut row);
+        row.fill_auto(false);
+
+        row.validate()?;
+        if row.get_id() != id && self.exists(row.get_id())? {
+            return Err(Error {
+                kind: ErrorKind::AlreadyExists,
+                message: "id already exists".to_string()
+            })
+        }
+        self.check_unique(&row, id)?;
+
+        self.sequence.advance(row.get_id())?;
+        self.rewrite(id, old_values, &row, begin, end)?;
+
+        Ok(row)
+    }
+
+    /// Values of the secondary indexed columns of `row`, in the order of `secondary_indexes`.
+    fn indexed_values(&self, row: &S) -> Vec<Option<DType>> {
+        self.secondary_indexes
+            .iter()
+            .map(|index| row.get(index.spec.field.to_string()))
+            .collect()
+    }
+
+    /// Writes `row` in place of the row `id` stored between `begin` and `end`, whose secondary
+    /// indexed values were `old_values`. Only the indexes of changed columns are updated.
+    fn rewrite(&mut self, id: i32, old_values: Vec<Option<DType>>, row: &S, begin: u64, end: u64) -> Result<(), Error> {
+        self.file.erase(begin, end)?;
+        let (written_pos, _) = self.file.write_row(row)?;
+        if let Some(index) = &mut self.index {
+            index.shift(end, end - begin);
+            index.delete(id);
+            index.set(row.get_id(), written_pos);
+        }
+        for (index, old_value) in self.secondary_indexes.iter_mut().zip(old_values) {
+            let value = row.get(index.spec.field.to_string());
+            if value == old_value && row.get_id() == id {
+                continue;
+            }
+            if let Some(old_value) = old_value {
+                index.remove(&old_value, id);
+            }
+            if let Some(value) = value {
+                index.insert(value, row.get_id());
+            }
+        }
+
+        Ok(())
     }
 
     pub fn delete(&mut self, id: i32) -> Result<(), Error> {
-        let (row, begin, end) = match self.find(id)? {
+        let (_, begin, end) = match self.find(id)? {
             Some(e) => e,
             None => return Err(Error {kind: ErrorKind::NotFound, message: "record not found".to_string()}),
         };
         self.file.erase(begin, end)?;
-        self.refresh_indexes();
+        self.refresh_indexes()?;
 
-        return Ok(());
+        Ok(())
     }
 
     /// Returns a tuple of (TableSchema, begin, end), where begin & end are byte-level dimensions
     /// of a given row.
     fn find(&mut self, id: i32) -> Result<Option<(S, u64, u64)>, Error> {
-        self.file.seek(0)?;
+        if let Some(index) = &self.index {
+            return match index.get(id) {
+                Some(pos) => {
+                    self.file.seek(pos as i64)?;
+                    Ok(self.file.read_row()?.filter(|(row, _, _)| row.get_id() == id))
+                }
+                None => Ok(None),
+            };
+        }
+        self.file.rewind()?;
 
         loop {
             match self.file.read_row()? {
@@ -360,22 +854,28 @@ impl<S: TableSchema> Table<S> {
     }
 
     fn refresh_indexes(&mut self) -> Result<(), Error> {
-        let mut index = match &mut self.index {
-            Some(i) => i,
-            None => return Ok(()),
-        };
+        if self.index.is_none() && self.secondary_indexes.is_empty() {
+            return Ok(());
+        }
 
-        self.file.seek(0);
-        index.clear();
+        self.file.rewind()?;
+        if let Some(index) = &mut self.index {
+            index.clear();
+        }
+        for index in &mut self.secondary_indexes {
+            index.clear();
+        }
 
-        loop {
-            match self.file.read_row()? {
-                Some((row, begin, end)) => {
-                    index.set(row.get_id(), begin);
+        while let Some((row, begin, _)) = self.file.read_row()? {
+            if let Some(index) = &mut self.index {
+                index.set(row.get_id(), begin);
+            }
+            for index in &mut self.secondary_indexes {
+                if let Some(value) = row.get(index.spec.field.to_string()) {
+                    index.insert(value, row.get_id());
                 }
-                None => break,
             }
-        };
+        }
 
         Ok(())
     }
@@ -384,3 +884,123 @@ impl<S: TableSchema> Table<S> {
         S::print_info();
     }
 }
+
+
+/// Lazy iterator over rows of a table, see `Table::iter` and `Table::select_iter`. Rows are
+/// read as the cursor advances, and it stops after yielding an error.
+pub struct Cursor<'a, S: TableSchema> {
+    table: &'a mut Table<S>,
+    source: CursorSource,
+    filter: Option<Filter>,
+}
+
+enum CursorSource {
+    /// Reading the file in order, the next row starting at the given offset.
+    Scan(u64),
+    /// Reading the rows with the given ids through the primary index.
+    Ids(std::vec::IntoIter<i32>),
+    /// Error to yield before stopping.
+    Failed(Error),
+    Done,
+}
+
+impl<'a, S: TableSchema> Cursor<'a, S> {
+    fn read(&mut self) -> Result<Option<S>, Error> {
+        match &mut self.source {
+            CursorSource::Scan(pos) => {
+                self.table.file.seek(*pos as i64)?;
+                match self.table.file.read_row()? {
+                    Some((row, _, end)) => {
+                        *pos = end;
+                        Ok(Some(row))
+                    }
+                    None => Ok(None),
+                }
+            }
+            CursorSource::Ids(ids) => {
+                for id in ids {
+                    if let Some((row, _, _)) = self.table.find(id)? {
+                        return Ok(Some(row));
+                    }
+                }
+                Ok(None)
+            }
+            CursorSource::Failed(_) => match std::mem::replace(&mut self.source, CursorSource::Done) {
+                CursorSource::Failed(e) => Err(e),
+                _ => unreachable!(),
+            },
+            CursorSource::Done => Ok(None),
+        }
+    }
+}
+
+impl<'a, S: TableSchema> Iterator for Cursor<'a, S> {
+    type Item = Result<S, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.read() {
+                Ok(Some(row)) if self.filter.as_ref().is_none_or(|filter| filter.matches(&row)) => {
+                    return Some(Ok(row));
+                }
+                Ok(Some(_)) => continue,
+                Ok(None) => {
+                    self.source = CursorSource::Done;
+                    return None;
+                }
+                Err(e) => {
+                    self.source = CursorSource::Done;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+
+/// Skips `offset` rows and returns up to `limit` of the following ones, without reading
+/// further. Fails on the first error, including one among the skipped rows.
+fn window<S, I: Iterator<Item = Result<S, Error>>>(mut rows: I, offset: usize, limit: usize) -> Result<Vec<S>, Error> {
+    let mut result = Vec::new();
+    let mut skipped = 0;
+    while result.len() < limit {
+        match rows.next() {
+            Some(row) if skipped < offset => {
+                row?;
+                skipped += 1;
+            }
+            Some(row) => result.push(row?),
+            None => break,
+        }
+    }
+    Ok(result)
+}
+
+
+/// Row being sorted by `Table::select_by`; `seq` is its position in the table, which
+/// breaks ties.
+struct Ranked<S> {
+    key: Vec<SortKey>,
+    seq: usize,
+    row: S,
+}
+
+impl<S> PartialEq for Ranked<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S> Eq for Ranked<S> {}
+
+impl<S> PartialOrd for Ranked<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Ranked<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key).then(self.seq.cmp(&other.seq))
+    }
+}
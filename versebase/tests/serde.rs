@@ -0,0 +1,81 @@
+#![cfg(feature = "serde")]
+
+#[macro_use]
+extern crate versebase_derive;
+
+use versebase::datatypes::{DataType, DType, DateTime, Int, List, Str, VarChar};
+use versebase::table::TableSchema;
+
+
+#[derive(VersebaseEnum, Debug, Clone, PartialEq)]
+pub enum Language {
+    En,
+    Uk,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct Lyrics {
+    pub id: Int,
+    pub title: VarChar<8>,
+    pub language: Language,
+    pub tags: List<Str>,
+    pub written_at: DateTime,
+}
+
+#[test]
+fn test_row_round_trip() {
+    let lyrics = Lyrics::new(
+        Int::new(1),
+        VarChar::new("Underdog".into()),
+        Language::Uk,
+        List::new(vec!["rock".into()]),
+        DateTime::new(chrono::NaiveDate::from_ymd(2009, 6, 1).and_hms(12, 0, 0)),
+    );
+    let json = serde_json::to_value(&lyrics).unwrap();
+    assert_eq!(json, serde_json::json!({
+        "id": 1,
+        "title": "Underdog",
+        "language": "Uk",
+        "tags": ["rock"],
+        "written_at": "2009-06-01T12:00:00",
+    }));
+
+    let parsed: Lyrics = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.to_map(), lyrics.to_map());
+}
+
+#[test]
+fn test_row_deserialization_errors() {
+    let row = serde_json::json!({
+        "id": 1, "title": "Club foot", "language": "Uk", "tags": [], "written_at": "2004-01-01T00:00:00",
+    });
+    let error = serde_json::from_value::<Lyrics>(row).unwrap_err();
+    assert!(error.to_string().contains("at most 8"), "{}", error);
+
+    let row = serde_json::json!({
+        "id": 1, "title": "Shoot", "language": "Fr", "tags": [], "written_at": "2004-01-01T00:00:00",
+    });
+    let error = serde_json::from_value::<Lyrics>(row).unwrap_err();
+    assert!(error.to_string().contains("expected one of: En, Uk"), "{}", error);
+
+    let row = serde_json::json!({
+        "id": 1, "title": "Shoot", "language": "En", "tags": [], "written_at": "2004-01-01T00:00:00", "bpm": 120,
+    });
+    assert!(serde_json::from_value::<Lyrics>(row).is_err());
+}
+
+#[test]
+fn test_dtype_round_trip() {
+    let values = vec![
+        DType::Int(Int::new(3)),
+        DType::Str(Str::new("Kasabian".into())),
+        DType::from(Language::En),
+        DType::List(vec![DType::Int(Int::new(1)), DType::Int(Int::new(2))]),
+    ];
+    let json = serde_json::to_value(&values).unwrap();
+    assert_eq!(json[0], serde_json::json!({"Int": 3}));
+    assert_eq!(json[2], serde_json::json!({"Enum": {"type_name": "Language", "variant": "En", "discriminant": 0}}));
+
+    let parsed: Vec<DType> = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, values);
+}
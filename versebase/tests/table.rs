@@ -0,0 +1,925 @@
+#[macro_use]
+extern crate versebase_derive;
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use versebase::datatypes::{DataKind, DataType, DateTime, Decimal, DecimalValue, DType, EnumType, Int, Json, List, Str, Timestamp, VarChar};
+use versebase::error::ErrorKind;
+use versebase::index::IndexSpec;
+use versebase::query::{Column, Filter, Order, QueryBuilder, Select};
+use versebase::schema::ColumnInfo;
+use versebase::table::{Table, TableSchema, WriteAction};
+
+
+#[derive(TableSchema, Debug)]
+pub struct Songs {
+    pub id: Int,
+    pub name: Str,
+    pub artist_id: Int,
+}
+
+#[derive(VersebaseEnum, Debug, Clone, PartialEq)]
+pub enum Language {
+    En,
+    Uk,
+    De = 10,
+}
+
+#[derive(TableSchema, Debug)]
+#[versebase(validate = check_lyrics)]
+pub struct Lyrics {
+    pub id: Int,
+    pub text: Str,
+    pub language: Language,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct Albums {
+    pub id: Int,
+    pub genres: List<Str>,
+    pub artist_ids: List<Int>,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct SongStats {
+    pub id: Int,
+    pub stats: Json,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct Artists {
+    pub id: Int,
+    pub name: VarChar<8>,
+}
+
+#[derive(TableSchema, Debug)]
+#[versebase(table = "accounts")]
+pub struct Users {
+    #[versebase(primary_key)]
+    pub user_id: Int,
+    #[versebase(unique, validate = has_at_sign)]
+    pub email: Str,
+    #[versebase(index, validate = is_country_code)]
+    pub country: Str,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct Playlists {
+    pub id: Int,
+    pub name: Str,
+    #[versebase(default = 0, index)]
+    pub plays: Int,
+    #[versebase(default = "private")]
+    pub visibility: Str,
+    #[versebase(auto_now_add)]
+    pub created_at: Timestamp,
+    #[versebase(auto_now, index)]
+    pub updated_at: DateTime,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct LikedSongs {
+    pub id: Int,
+    pub song: Str,
+    #[versebase(index)]
+    pub user_id: Int,
+    pub created_at: Timestamp,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct Subscriptions {
+    pub id: Int,
+    pub price: Decimal<5, 2>,
+}
+
+fn has_at_sign(email: &Str) -> Result<(), &'static str> {
+    match email.get().contains('@') {
+        true => Ok(()),
+        false => Err("must contain @"),
+    }
+}
+
+fn is_country_code(country: &Str) -> Result<(), String> {
+    let code = country.get();
+    match code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        true => Ok(()),
+        false => Err(format!("{:?} is not a 2-letter country code", code)),
+    }
+}
+
+fn check_lyrics(lyrics: &Lyrics) -> Result<(), versebase::error::Error> {
+    match lyrics.text.get().trim().is_empty() {
+        true => Err(versebase::error::Error::validation("text", "must not be blank")),
+        false => Ok(()),
+    }
+}
+
+#[derive(TableSchema, Debug)]
+pub struct Tracks {
+    pub id: Int,
+    pub name: Str,
+    #[versebase(references = Artists)]
+    pub artist_id: Int,
+}
+
+#[derive(TableSchema, Debug)]
+pub struct Features {
+    pub id: Int,
+    #[versebase(references = Artists)]
+    pub artist_id: Int,
+    #[versebase(references = Artists)]
+    pub guest_id: Int,
+}
+
+/// Returns a fresh directory for a single test's table files.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("versebase_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Opens the table of `S` in `dir` along with its index.
+fn open<S: TableSchema>(dir: &Path) -> Table<S> {
+    Table::<S>::open(dir).unwrap()
+}
+
+/// Opens `name.tbl` in `dir` as a table of `S`, without an index.
+fn open_unindexed<S: TableSchema>(dir: &Path, name: &str) -> Result<Table<S>, versebase::error::Error> {
+    Table::<S>::new(name.to_string(), Box::from(dir.join(format!("{}.tbl", name)).as_path()), None)
+}
+
+fn user(id: i32, email: &str, country: &str) -> Users {
+    Users::new(Int::new(id), Str::new(email.into()), Str::new(country.into()))
+}
+
+#[test]
+fn test_decode_error_names_table_column_and_offset() {
+    let dir = test_dir("decode_error");
+    let mut songs = open::<Songs>(&dir);
+    songs.create(Songs::new(Int::new(1), Str::new("Underdog".into()), Int::new(2))).unwrap();
+    drop(songs);
+
+    // Append a row whose `id` is 3 bytes long
+    let mut file = OpenOptions::new().append(true).open(dir.join("songs.tbl")).unwrap();
+    let offset = file.metadata().unwrap().len();
+    file.write_all(&[1, 0, 0, 255, 0, 255, 0, 255, 0, 255, 0]).unwrap();
+    file.write_all(b"Club foot").unwrap();
+    file.write_all(&[255, 0, 255, 0, 255, 0, 255, 0, 2, 0, 0, 0]).unwrap();
+    file.write_all(&[0, 127, 0, 255, 0, 127, 0, 255]).unwrap();
+    drop(file);
+
+    let mut songs = open_unindexed::<Songs>(&dir, "songs").unwrap();
+    let error = songs.select([].into()).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Decode));
+    assert!(error.message.contains("table \"songs\""), "{}", error.message);
+    assert!(error.message.contains("column \"id\""), "{}", error.message);
+    assert!(error.message.contains(&format!("offset {}", offset)), "{}", error.message);
+
+    // A cursor yields the rows before the broken one, then the error, then stops
+    let mut rows = songs.iter();
+    assert_eq!(rows.next().unwrap().unwrap().name.get(), "Underdog");
+    assert!(matches!(rows.next().unwrap().unwrap_err().kind, ErrorKind::Decode));
+    assert!(rows.next().is_none());
+}
+
+#[test]
+fn test_enum_column() {
+    assert_eq!(Language::De.discriminant(), 10);
+    assert_eq!(Language::from_discriminant(1), Some(Language::Uk));
+    assert_eq!(Language::from_discriminant(2), None);
+    assert_eq!(Language::from_(&Language::De.serialize()).unwrap(), Language::De);
+    assert!(matches!(
+        Language::from_(&2i32.to_ne_bytes()),
+        Err(versebase::error::Error {kind: ErrorKind::Decode, ..})
+    ));
+
+    let dir = test_dir("enum_column");
+    let mut lyrics = open::<Lyrics>(&dir);
+    lyrics.create(Lyrics::new(Int::new(1), Str::new("Hello".into()), Language::En)).unwrap();
+    lyrics.create(Lyrics::new(Int::new(2), Str::new("Привіт".into()), Language::Uk)).unwrap();
+    lyrics.create(Lyrics::new(Int::new(3), Str::new("Hallo".into()), Language::De)).unwrap();
+
+    assert_eq!(lyrics.get(3).unwrap().language, Language::De);
+
+    let ukrainian = lyrics.select([("language".to_string(), DType::from(Language::Uk))].into()).unwrap();
+    assert_eq!(ukrainian.len(), 1);
+    assert_eq!(ukrainian[0].id.get(), 2);
+}
+
+#[test]
+fn test_list_column() {
+    let dir = test_dir("list_column");
+    let mut albums = open::<Albums>(&dir);
+    albums.create(Albums::new(
+        Int::new(1),
+        List::new(vec!["rock".into(), "indie".into()]),
+        List::new(vec![2]),
+    )).unwrap();
+    albums.create(Albums::new(
+        Int::new(2),
+        List::new(vec!["thrash metal".into()]),
+        List::new(vec![1, 2]),
+    )).unwrap();
+
+    assert_eq!(albums.get(2).unwrap().artist_ids.get(), vec![1, 2]);
+
+    let rock = albums.select_where(&Filter::Contains(Column::from("genres"), DType::Str(Str::new("rock".into())))).unwrap();
+    assert_eq!(rock.len(), 1);
+    assert_eq!(rock[0].id.get(), 1);
+
+    let featuring_2 = albums.select_where(&Filter::Contains(Column::from("artist_ids"), DType::Int(Int::new(2)))).unwrap();
+    assert_eq!(featuring_2.len(), 2);
+
+    let both = albums.select_where(&Filter::And(vec![
+        Filter::Contains(Column::from("artist_ids"), DType::Int(Int::new(1))),
+        Filter::Contains(Column::from("artist_ids"), DType::Int(Int::new(2))),
+    ])).unwrap();
+    assert_eq!(both.len(), 1);
+    assert_eq!(both[0].id.get(), 2);
+}
+
+#[test]
+fn test_json_column() {
+    let dir = test_dir("json_column");
+    let mut stats = open::<SongStats>(&dir);
+    stats.create(SongStats::new(Int::new(1), Json::new(serde_json::json!({"stats": {"plays": 42}})))).unwrap();
+    stats.create(SongStats::new(Int::new(2), Json::new(serde_json::json!({"stats": {"plays": 7}})))).unwrap();
+    stats.create(SongStats::new(Int::new(3), Json::new(serde_json::json!({})))).unwrap();
+
+    let plays = Column::json("stats", "$.stats.plays").unwrap();
+    let popular = stats.select_where(
+        &Filter::Eq(plays.clone(), DType::Json(Json::new(serde_json::json!(42))))
+    ).unwrap();
+    assert_eq!(popular.len(), 1);
+    assert_eq!(popular[0].id.get(), 1);
+
+    let projection = stats.project(&Filter::And(vec![]), &["id".into(), plays]).unwrap();
+    assert_eq!(projection, vec![
+        vec![Some(DType::Int(Int::new(1))), Some(DType::Json(Json::new(serde_json::json!(42))))],
+        vec![Some(DType::Int(Int::new(2))), Some(DType::Json(Json::new(serde_json::json!(7))))],
+        vec![Some(DType::Int(Int::new(3))), None],
+    ]);
+
+    stats.create(SongStats::new(Int::new(4), Json::new(serde_json::json!({"stats": {"plays": null}})))).unwrap();
+    let ids = |rows: Vec<SongStats>| rows.iter().map(|row| row.id.get()).collect::<Vec<_>>();
+    let plays = Column::json("stats", "$.stats.plays").unwrap();
+    assert_eq!(ids(stats.select_where(&Filter::IsNull(plays.clone())).unwrap()), vec![3, 4]);
+    assert_eq!(ids(stats.select_where(&Filter::Not(Box::new(Filter::IsNull(plays)))).unwrap()), vec![1, 2]);
+}
+
+#[test]
+fn test_bounded_string_validation() {
+    let dir = test_dir("bounded_string_validation");
+    let mut artists = open::<Artists>(&dir);
+
+    let error = artists.create(Artists::new(Int::new(1), VarChar::new("Slayer Slayer".into()))).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation));
+    assert!(error.message.starts_with("column \"name\""), "{}", error.message);
+    assert!(artists.get(1).is_err());
+
+    artists.create(Artists::new(Int::new(1), VarChar::new("Slayer".into()))).unwrap();
+    artists.create(Artists::new(Int::new(2), VarChar::new("Kasabian".into()))).unwrap();
+
+    let error = artists.update(Artists::new(Int::new(1), VarChar::new("Slayer Slayer".into()))).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation));
+    assert_eq!(artists.get(1).unwrap().name.get(), "Slayer");
+
+    artists.update(Artists::new(Int::new(1), VarChar::new("Slay".into()))).unwrap();
+    assert_eq!(artists.get(1).unwrap().name.get(), "Slay");
+    assert_eq!(artists.get(2).unwrap().name.get(), "Kasabian");
+}
+
+#[test]
+fn test_from_strings() {
+    let lyrics = Lyrics::from_strings([
+        ("id".to_string(), "7".to_string()),
+        ("text".to_string(), "Hello".to_string()),
+        ("language".to_string(), "De".to_string()),
+    ].into()).unwrap();
+    assert_eq!(lyrics.id.get(), 7);
+    assert_eq!(lyrics.language, Language::De);
+    assert_eq!(lyrics.language.format(), "De");
+
+    let error = Lyrics::from_strings([
+        ("id".to_string(), "7".to_string()),
+        ("text".to_string(), "Hello".to_string()),
+        ("language".to_string(), "de".to_string()),
+    ].into()).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Parse));
+    assert_eq!(error.message, "column \"language\": \"de\" is not a valid Language, expected one of: En, Uk, De");
+
+    let error = Lyrics::from_strings([("id".to_string(), "7".to_string())].into()).unwrap_err();
+    assert_eq!(error.message, "column \"text\" is missing");
+
+    assert_eq!(Lyrics::parse_value("id", "3").unwrap(), DType::Int(Int::new(3)));
+    assert_eq!(Lyrics::parse_value("language", "Uk").unwrap(), DType::from(Language::Uk));
+    assert!(matches!(Lyrics::parse_value("lang", "Uk").unwrap_err().kind, ErrorKind::NotFound));
+}
+
+#[test]
+fn test_range_filters_and_min_max() {
+    let dir = test_dir("range_filters");
+    let mut songs = open::<Songs>(&dir);
+    songs.create(Songs::new(Int::new(1), Str::new("Underdog".into()), Int::new(2))).unwrap();
+    songs.create(Songs::new(Int::new(2), Str::new("Club foot".into()), Int::new(2))).unwrap();
+    songs.create(Songs::new(Int::new(3), Str::new("Angel of death".into()), Int::new(1))).unwrap();
+
+    let ids = |rows: Vec<Songs>| rows.iter().map(|row| row.id.get()).collect::<Vec<_>>();
+    let between = Filter::And(vec![
+        Filter::Ge("id".into(), DType::Int(Int::new(2))),
+        Filter::Lt("id".into(), DType::from(versebase::datatypes::BigInt::new(3))),
+    ]);
+    assert_eq!(ids(songs.select_where(&between).unwrap()), vec![2]);
+    assert_eq!(ids(songs.select_where(&Filter::Gt("name".into(), DType::Str(Str::new("B".into())))).unwrap()), vec![1, 2]);
+    let error = songs.select_where(&Filter::Le("nonexistent".into(), DType::Int(Int::new(9)))).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::NotFound));
+    assert_eq!(error.message, "unknown column \"nonexistent\"");
+
+    let all = Filter::And(vec![]);
+    assert_eq!(songs.min(&all, &"name".into()).unwrap(), Some(DType::Str(Str::new("Angel of death".into()))));
+    assert_eq!(songs.max(&all, &"name".into()).unwrap(), Some(DType::Str(Str::new("Underdog".into()))));
+    let by_artist = Filter::Eq("artist_id".into(), DType::Int(Int::new(2)));
+    assert_eq!(songs.min(&by_artist, &"id".into()).unwrap(), Some(DType::Int(Int::new(1))));
+    assert_eq!(songs.max(&Filter::Eq("artist_id".into(), DType::Int(Int::new(5))), &"id".into()).unwrap(), None);
+}
+
+#[test]
+fn test_schema_attributes() {
+    assert_eq!(Songs::table_name(), "songs");
+    assert_eq!(Songs::primary_key(), "id");
+    assert_eq!(Songs::indexes(), vec![]);
+    assert_eq!(SongStats::table_name(), "song_stats");
+    assert_eq!(Users::table_name(), "accounts");
+    assert_eq!(Users::primary_key(), "user_id");
+    assert_eq!(Users::indexes(), vec![
+        IndexSpec { field: "email", unique: true },
+        IndexSpec { field: "country", unique: false },
+    ]);
+
+    let dir = test_dir("schema_attributes");
+    let mut users = open::<Users>(&dir);
+    assert_eq!(users.name, "accounts");
+    assert!(dir.join("accounts.tbl").exists() && dir.join("accounts.idx").exists());
+    assert_eq!(users.create(user(1, "tom@example.com", "US")).unwrap(), 1);
+    users.create(user(2, "serj@example.com", "AM")).unwrap();
+    users.create(user(3, "kerry@example.com", "US")).unwrap();
+    assert_eq!(users.get(2).unwrap().email.get(), "serj@example.com");
+
+    let error = users.create(user(4, "tom@example.com", "UA")).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::AlreadyExists));
+    assert_eq!(error.message, "column \"email\" is unique, \"tom@example.com\" already exists");
+    assert!(users.get(4).is_err());
+
+    // Keeping its own value is fine, taking another row's is not
+    users.update(user(1, "tom@example.com", "UK")).unwrap();
+    let error = users.update(user(1, "serj@example.com", "UK")).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::AlreadyExists));
+
+    let ids = |rows: Vec<Users>| rows.iter().map(|row| row.user_id.get()).collect::<Vec<_>>();
+    let by_country = |country: &str| Filter::Eq("country".into(), DType::Str(Str::new(country.into())));
+    assert_eq!(ids(users.select_where(&by_country("US")).unwrap()), vec![3]);
+    assert_eq!(ids(users.select_where(&by_country("UK")).unwrap()), vec![1]);
+    drop(users);
+
+    // Secondary indexes are rebuilt when the table is opened again
+    let mut users = open::<Users>(&dir);
+    users.delete(3).unwrap();
+    assert_eq!(ids(users.select_where(&by_country("US")).unwrap()), Vec::<i32>::new());
+    users.create(user(3, "kerry@example.com", "US")).unwrap();
+    assert!(users.create(user(5, "serj@example.com", "US")).is_err());
+    assert_eq!(ids(users.select_where(&Filter::And(vec![
+        by_country("US"),
+        Filter::Eq("email".into(), DType::Str(Str::new("kerry@example.com".into()))),
+    ])).unwrap()), vec![3]);
+}
+
+#[test]
+fn test_create_without_index() {
+    let dir = test_dir("create_without_index");
+    let mut songs = open_unindexed::<Songs>(&dir, "songs").unwrap();
+    songs.create(Songs::new(Int::new(1), Str::new("Underdog".into()), Int::new(2))).unwrap();
+    songs.create(Songs::new(Int::new(2), Str::new("Club foot".into()), Int::new(2))).unwrap();
+    let error = songs.create(Songs::new(Int::new(1), Str::new("Shoot".into()), Int::new(2))).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::AlreadyExists));
+    assert_eq!(songs.get(2).unwrap().name.get(), "Club foot");
+}
+
+#[test]
+fn test_relations() {
+    let dir = test_dir("relations");
+    let mut tracks = open::<Tracks>(&dir);
+    let mut artists = open::<Artists>(&dir);
+    let mut features = open::<Features>(&dir);
+
+    artists.create(Artists::new(Int::new(1), VarChar::new("Slayer".into()))).unwrap();
+    artists.create(Artists::new(Int::new(2), VarChar::new("Kasabian".into()))).unwrap();
+    tracks.create(Tracks::new(Int::new(1), Str::new("Underdog".into()), Int::new(2))).unwrap();
+    tracks.create(Tracks::new(Int::new(2), Str::new("Club foot".into()), Int::new(2))).unwrap();
+    tracks.create(Tracks::new(Int::new(3), Str::new("Raining blood".into()), Int::new(1))).unwrap();
+    tracks.create(Tracks::new(Int::new(4), Str::new("Demo".into()), Int::new(9))).unwrap();
+    features.create(Features::new(Int::new(1), Int::new(2), Int::new(1))).unwrap();
+
+    assert!(Tracks::indexes().contains(&IndexSpec { field: "artist_id", unique: false }));
+
+    let track = tracks.get(1).unwrap();
+    assert_eq!(track.artist(&mut artists).unwrap().name.get(), "Kasabian");
+    let error = tracks.get(4).unwrap().artist(&mut artists).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::NotFound));
+
+    let kasabian = artists.get(2).unwrap();
+    let names: Vec<String> = kasabian.tracks(&mut tracks).unwrap().iter().map(|track| track.name.get()).collect();
+    assert_eq!(names, vec!["Underdog", "Club foot"]);
+    assert_eq!(artists.get(1).unwrap().tracks(&mut tracks).unwrap().len(), 1);
+
+    let feature = features.get(1).unwrap();
+    assert_eq!(feature.artist(&mut artists).unwrap().name.get(), "Kasabian");
+    assert_eq!(feature.guest(&mut artists).unwrap().name.get(), "Slayer");
+    assert_eq!(kasabian.features_by_artist(&mut features).unwrap().len(), 1);
+    assert_eq!(kasabian.features_by_guest(&mut features).unwrap().len(), 0);
+}
+
+
+#[test]
+fn test_decimal_column() {
+    let dir = test_dir("decimal_column");
+    let mut subscriptions = open::<Subscriptions>(&dir);
+    subscriptions.create(Subscriptions::new(Int::new(1), Decimal::new(DecimalValue::new(499, 2)))).unwrap();
+    subscriptions.create(Subscriptions::new(Int::new(2), Decimal::new(DecimalValue::new(999, 2)))).unwrap();
+
+    let error = subscriptions.create(Subscriptions::new(Int::new(3), Decimal::new(DecimalValue::new(1000, 0)))).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::OutOfRange));
+    assert!(error.message.starts_with("column \"price\""), "{}", error.message);
+    assert!(subscriptions.get(3).is_err());
+
+    // Values that don't fit the column still make a valid filter
+    let ids = |rows: Vec<Subscriptions>| rows.iter().map(|row| row.id.get()).collect::<Vec<i32>>();
+    let query = Subscriptions::query().price().lt(DecimalValue::new(1000005, 3));
+    assert_eq!(ids(subscriptions.execute(query).unwrap()), vec![1, 2]);
+    let query = Subscriptions::query().price().gt(DecimalValue::new(4995, 3));
+    assert_eq!(ids(subscriptions.execute(query).unwrap()), vec![2]);
+}
+
+#[test]
+fn test_typed_query() {
+    let dir = test_dir("typed_query");
+    let mut songs = open::<Songs>(&dir);
+    songs.create(Songs::new(Int::new(1), Str::new("Underdog".into()), Int::new(2))).unwrap();
+    songs.create(Songs::new(Int::new(2), Str::new("Club foot".into()), Int::new(2))).unwrap();
+    songs.create(Songs::new(Int::new(3), Str::new("Club tropicana".into()), Int::new(3))).unwrap();
+    let ids = |rows: Vec<Songs>| rows.iter().map(|song| song.id.get()).collect::<Vec<i32>>();
+
+    let query = Songs::query().artist_id().eq(2).name().starts_with("Club");
+    assert_eq!(ids(songs.execute(query).unwrap()), vec![2]);
+    assert_eq!(ids(songs.execute(Songs::query().id().ge(2)).unwrap()), vec![2, 3]);
+    assert_eq!(ids(songs.execute(Songs::query().name().eq("Underdog")).unwrap()), vec![1]);
+    assert_eq!(ids(songs.execute(Songs::query()).unwrap()), vec![1, 2, 3]);
+
+    let query = Songs::query()
+        .filter(Filter::Lt(SongsColumn::Id.into(), DType::Int(Int::new(3))))
+        .artist_id().gt(1);
+    assert_eq!(ids(songs.execute(query).unwrap()), vec![1, 2]);
+
+    assert_eq!(SongsColumn::ALL, &[SongsColumn::Id, SongsColumn::Name, SongsColumn::ArtistId]);
+    assert_eq!(SongsColumn::ArtistId.to_string(), "artist_id");
+    assert_eq!(Column::from(SongsColumn::Name), Column::Field("name".into()));
+
+    let error = songs.select([("title".to_string(), DType::Str(Str::new("Underdog".into())))].into()).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::NotFound));
+}
+
+
+#[test]
+fn test_patch() {
+    let dir = test_dir("patch");
+    let mut users = open::<Users>(&dir);
+    users.create(user(1, "tom@example.com", "US")).unwrap();
+    users.create(user(2, "serj@example.com", "AM")).unwrap();
+    users.create(user(3, "kerry@example.com", "US")).unwrap();
+
+    let patched = users.patch(1, UsersPatch { country: Some(Str::new("UK".into())), ..Default::default() }).unwrap();
+    assert_eq!((patched.email.get(), patched.country.get()), ("tom@example.com".to_string(), "UK".to_string()));
+    assert_eq!(users.get(1).unwrap().country.get(), "UK");
+    // Rows stored after the patched one are still found by the primary index
+    assert_eq!(users.get(2).unwrap().email.get(), "serj@example.com");
+    assert_eq!(users.get(3).unwrap().email.get(), "kerry@example.com");
+
+    let ids = |rows: Vec<Users>| rows.iter().map(|row| row.user_id.get()).collect::<Vec<_>>();
+    assert_eq!(ids(users.execute(Users::query().country().eq("US")).unwrap()), vec![3]);
+    assert_eq!(ids(users.execute(Users::query().country().eq("UK")).unwrap()), vec![1]);
+
+    let error = users.patch(2, UsersPatch { email: Some(Str::new("tom@example.com".into())), ..Default::default() }).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::AlreadyExists));
+    assert_eq!(users.get(2).unwrap().email.get(), "serj@example.com");
+    let error = users.patch(9, UsersPatch::default()).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::NotFound));
+
+    // Changing the id keeps the row's unique values and moves its index entries
+    users.patch(3, UsersPatch { user_id: Some(Int::new(4)), ..Default::default() }).unwrap();
+    assert!(users.get(3).is_err());
+    assert_eq!(ids(users.execute(Users::query().country().eq("US")).unwrap()), vec![4]);
+    let error = users.patch(4, UsersPatch { user_id: Some(Int::new(1)), ..Default::default() }).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::AlreadyExists));
+    drop(users);
+
+    let mut users = open::<Users>(&dir);
+    assert_eq!(ids(users.select([].into()).unwrap()), vec![2, 1, 4]);
+    assert_eq!(users.get(4).unwrap().email.get(), "kerry@example.com");
+}
+
+
+#[test]
+fn test_schema_info() {
+    let schema = Users::schema();
+    assert_eq!(schema.table, "accounts");
+    assert_eq!(schema.columns[1], ColumnInfo {
+        name: "email",
+        ordinal: 1,
+        kind: DataKind::Str,
+        nullable: false,
+        primary_key: false,
+        indexed: true,
+        unique: true,
+    });
+    assert_eq!(schema.primary_key().name, "user_id");
+    assert!(schema.column("country").unwrap().indexed);
+    assert!(schema.column("missing").is_none());
+    assert_eq!(schema.to_string(), "accounts (\n    user_id Int primary key,\n    email Str unique,\n    country Str index\n)");
+
+    let kinds = |columns: Vec<ColumnInfo>| columns.into_iter().map(|column| column.kind.to_string()).collect::<Vec<_>>();
+    assert_eq!(kinds(Artists::schema().columns), vec!["Int", "VarChar<8>"]);
+    assert_eq!(kinds(Lyrics::schema().columns), vec!["Int", "Str", "Language"]);
+    assert_eq!(kinds(Albums::schema().columns), vec!["Int", "List<Str>", "List<Int>"]);
+    assert_eq!(Albums::schema().columns[1].kind, DataKind::List(Box::new(DataKind::Str)));
+
+    assert!(SongStats::schema().column("stats").unwrap().nullable);
+    assert_eq!(SongStats::schema().to_string(), "song_stats (\n    id Int primary key,\n    stats Json null\n)");
+
+    // Only the names and types of the columns count, not keys or indexes
+    assert_eq!(Songs::schema().fingerprint(), Tracks::schema().fingerprint());
+    assert_ne!(Songs::schema().fingerprint(), Artists::schema().fingerprint());
+    assert_ne!(Songs::schema().fingerprint(), Features::schema().fingerprint());
+}
+
+#[test]
+fn test_file_header() {
+    let dir = test_dir("file_header");
+    let mut songs = open::<Songs>(&dir);
+    songs.create(Songs::new(Int::new(1), Str::new("Underdog".into()), Int::new(2))).unwrap();
+    drop(songs);
+
+    let raw = fs::read(dir.join("songs.tbl")).unwrap();
+    assert_eq!(raw[..8], *b"vrsbtbl1");
+    assert_eq!(raw[8..16], Songs::schema().fingerprint().to_ne_bytes());
+
+    // Another layout is refused, the same one under another struct is fine
+    let error = open_unindexed::<Artists>(&dir, "songs").err().unwrap();
+    assert!(matches!(error.kind, ErrorKind::SchemaMismatch));
+    assert!(error.message.contains("table \"songs\""), "{}", error.message);
+    let mut tracks = open_unindexed::<Tracks>(&dir, "songs").unwrap();
+    assert_eq!(tracks.get(1).unwrap().name.get(), "Underdog");
+    drop(tracks);
+
+    // Files written before headers were kept get one
+    fs::write(dir.join("songs.tbl"), &raw[16..]).unwrap();
+    let mut songs = open::<Songs>(&dir);
+    assert_eq!(songs.get(1).unwrap().name.get(), "Underdog");
+    songs.create(Songs::new(Int::new(2), Str::new("Club foot".into()), Int::new(2))).unwrap();
+    assert_eq!(songs.select([].into()).unwrap().len(), 2);
+    assert_eq!(fs::read(dir.join("songs.tbl")).unwrap()[..16], raw[..16]);
+}
+
+
+#[test]
+fn test_validation_hooks() {
+    let dir = test_dir("validation_hooks");
+    let mut users = open::<Users>(&dir);
+    users.create(user(1, "tom@example.com", "US")).unwrap();
+
+    let error = users.create(user(2, "serj.example.com", "AM")).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation));
+    assert_eq!(error.message, "column \"email\": must contain @");
+    let error = users.update(user(1, "tom@example.com", "usa")).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation));
+    assert_eq!(error.message, "column \"country\": \"usa\" is not a 2-letter country code");
+    let error = users.patch(1, UsersPatch { email: Some(Str::new("tom".into())), ..Default::default() }).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation));
+    assert_eq!(users.get(1).unwrap().email.get(), "tom@example.com");
+    assert!(users.get(2).is_err());
+
+    let mut lyrics = open::<Lyrics>(&dir);
+    let error = lyrics.create(Lyrics::new(Int::new(1), Str::new("  ".into()), Language::En)).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation));
+    assert_eq!(error.message, "column \"text\": must not be blank");
+    lyrics.create(Lyrics::new(Int::new(1), Str::new("Hello".into()), Language::En)).unwrap();
+}
+
+
+#[test]
+fn test_defaults_and_auto_now() {
+    let dir = test_dir("defaults_and_auto_now");
+    let mut playlists = open::<Playlists>(&dir);
+
+    let before = Timestamp::now();
+    let playlist = Playlists::with_defaults(Int::new(1), Str::new("Road trip".into()));
+    assert_eq!((playlist.plays.get(), playlist.visibility.get()), (0, "private".to_string()));
+    assert!(playlist.created_at >= before);
+    playlists.create(playlist).unwrap();
+
+    // Inserting overwrites both timestamps, whatever the row had
+    let old = Timestamp::from_naive_utc(chrono::NaiveDate::from_ymd(2000, 1, 1).and_hms(0, 0, 0));
+    let mut playlist = Playlists::with_defaults(Int::new(2), Str::new("Gym".into()));
+    playlist.created_at = old.clone();
+    playlist.updated_at = DateTime::new(old.naive_utc());
+    playlists.create(playlist).unwrap();
+    let created = playlists.get(2).unwrap();
+    assert!(created.created_at >= before && created.updated_at.get() >= before.naive_utc());
+
+    // Updates refresh `auto_now` columns only
+    let mut playlist = playlists.get(1).unwrap();
+    playlist.created_at = old.clone();
+    playlist.updated_at = DateTime::new(old.naive_utc());
+    playlists.update(playlist).unwrap();
+    let updated = playlists.get(1).unwrap();
+    assert_eq!(updated.created_at, old);
+    assert!(updated.updated_at.get() >= before.naive_utc());
+
+    let patched = playlists.patch(1, PlaylistsPatch { plays: Some(Int::new(5)), ..Default::default() }).unwrap();
+    assert_eq!(patched.created_at, old);
+    assert!(patched.updated_at >= updated.updated_at);
+    let ids = |rows: Vec<Playlists>| rows.iter().map(|row| row.id.get()).collect::<Vec<_>>();
+    assert_eq!(ids(playlists.execute(Playlists::query().updated_at().eq(patched.updated_at.get())).unwrap()), vec![1]);
+    assert_eq!(ids(playlists.execute(Playlists::query().plays().eq(5)).unwrap()), vec![1]);
+}
+
+
+#[test]
+fn test_insert_auto() {
+    let dir = test_dir("insert_auto");
+    let mut songs = open::<Songs>(&dir);
+    let song = |name: &str| Songs::new(Int::new(0), Str::new(name.into()), Int::new(1));
+
+    assert_eq!(songs.insert_auto(song("Underdog")).unwrap(), 1);
+    assert_eq!(songs.insert_auto(song("Club foot")).unwrap(), 2);
+    assert_eq!(songs.get(2).unwrap().name.get(), "Club foot");
+    songs.create(Songs::new(Int::new(10), Str::new("Fire".into()), Int::new(1))).unwrap();
+    assert_eq!(songs.insert_auto(song("Shoot the runner")).unwrap(), 11);
+
+    // Ids of deleted rows aren't handed out again, even after reopening the table
+    songs.delete(11).unwrap();
+    songs.delete(10).unwrap();
+    drop(songs);
+    let mut songs = open::<Songs>(&dir);
+    assert_eq!(songs.insert_auto(song("Empire")).unwrap(), 12);
+    assert!(dir.join("songs.seq").exists());
+
+    // Tables written before sequences were kept continue after their highest id
+    fs::remove_file(dir.join("songs.seq")).unwrap();
+    let mut songs = open::<Songs>(&dir);
+    assert_eq!(songs.insert_auto(song("Processed beats")).unwrap(), 13);
+}
+
+
+#[test]
+fn test_upsert_and_insert_or_ignore() {
+    let dir = test_dir("upsert");
+    let mut users = open::<Users>(&dir);
+
+    assert_eq!(users.upsert(user(1, "tom@example.com", "US")).unwrap(), WriteAction::Inserted);
+    assert_eq!(users.upsert(user(2, "serj@example.com", "AM")).unwrap(), WriteAction::Inserted);
+    assert_eq!(users.upsert(user(1, "tom@example.com", "UK")).unwrap(), WriteAction::Replaced);
+    assert_eq!(users.get(1).unwrap().country.get(), "UK");
+    assert_eq!(users.get(2).unwrap().country.get(), "AM");
+
+    assert_eq!(users.insert_or_ignore(user(2, "serj@example.com", "US")).unwrap(), WriteAction::Ignored);
+    assert_eq!(users.get(2).unwrap().country.get(), "AM");
+    assert_eq!(users.insert_or_ignore(user(3, "kerry@example.com", "US")).unwrap(), WriteAction::Inserted);
+
+    let error = users.upsert(user(3, "tom@example.com", "US")).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::AlreadyExists));
+    let error = users.insert_or_ignore(user(4, "tom@example.com", "US")).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::AlreadyExists));
+    let error = users.upsert(user(4, "daron", "US")).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation));
+
+    let ids = |rows: Vec<Users>| rows.iter().map(|row| row.user_id.get()).collect::<Vec<_>>();
+    assert_eq!(ids(users.execute(Users::query().country().eq("US")).unwrap()), vec![3]);
+    assert_eq!(ids(users.execute(Users::query().country().eq("UK")).unwrap()), vec![1]);
+}
+
+
+#[test]
+fn test_insert_many() {
+    let dir = test_dir("insert_many");
+    let mut users = open::<Users>(&dir);
+    users.create(user(1, "tom@example.com", "US")).unwrap();
+
+    let ids = users.insert_many((2..=100).map(|id| user(id, &format!("user{}@example.com", id), "UA"))).unwrap();
+    assert_eq!(ids, (2..=100).collect::<Vec<_>>());
+    assert_eq!(users.get(57).unwrap().email.get(), "user57@example.com");
+    assert_eq!(users.execute(Users::query().country().eq("UA")).unwrap().len(), 99);
+    assert_eq!(users.insert_auto(user(0, "serj@example.com", "AM")).unwrap(), 101);
+
+    // Any conflict, stored or within the batch, leaves the table as it was
+    let conflicts = [
+        vec![user(200, "a@example.com", "DE"), user(1, "b@example.com", "DE")],
+        vec![user(200, "a@example.com", "DE"), user(200, "b@example.com", "DE")],
+        vec![user(200, "a@example.com", "DE"), user(201, "a@example.com", "DE")],
+        vec![user(200, "a@example.com", "DE"), user(201, "tom@example.com", "DE")],
+        vec![user(200, "a@example.com", "DE"), user(201, "b@example.com", "Germany")],
+    ];
+    for rows in conflicts {
+        assert!(users.insert_many(rows).is_err());
+        assert!(users.get(200).is_err());
+        assert!(users.execute(Users::query().country().eq("DE")).unwrap().is_empty());
+    }
+
+    // So does failing to record the new ids in the sequence file
+    fs::remove_file(dir.join("accounts.seq")).unwrap();
+    fs::create_dir(dir.join("accounts.seq")).unwrap();
+    let error = users.insert_many([user(300, "a@example.com", "DE")]).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Io));
+    assert_eq!(users.select([].into()).unwrap().len(), 101);
+    fs::remove_dir(dir.join("accounts.seq")).unwrap();
+    drop(users);
+
+    let mut users = open::<Users>(&dir);
+    assert_eq!(users.select([].into()).unwrap().len(), 101);
+    assert_eq!(users.get(100).unwrap().email.get(), "user100@example.com");
+    assert_eq!(users.insert_many(Vec::new()).unwrap(), Vec::<i32>::new());
+}
+
+
+#[test]
+fn test_cursor() {
+    let dir = test_dir("cursor");
+    let mut users = open::<Users>(&dir);
+    let country = |id: i32| if id % 2 == 0 { "UA" } else { "US" };
+    users.insert_many((1..=10).map(|id| user(id, &format!("user{}@example.com", id), country(id)))).unwrap();
+
+    let ids: Vec<i32> = users.iter().map(|row| row.unwrap().user_id.get()).collect();
+    assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+    let first: Vec<i32> = users.iter().take(3).map(|row| row.unwrap().user_id.get()).collect();
+    assert_eq!(first, vec![1, 2, 3]);
+
+    // Cursors are independent of each other and of other reads
+    let mut rows = users.select_iter(Users::query().country().eq("UA").into_filter());
+    assert_eq!(rows.next().unwrap().unwrap().user_id.get(), 2);
+    assert_eq!(rows.next().unwrap().unwrap().user_id.get(), 4);
+    drop(rows);
+    assert_eq!(users.get(9).unwrap().country.get(), "US");
+    let found = users
+        .select_iter(Filter::Gt("user_id".into(), DType::Int(Int::new(6))))
+        .find(|row| row.as_ref().map_or(true, |row| row.country.get() == "US"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.user_id.get(), 7);
+
+    let mut rows = users.select_iter(Filter::Eq("nickname".into(), DType::Int(Int::new(1))));
+    assert!(matches!(rows.next().unwrap().unwrap_err().kind, ErrorKind::NotFound));
+    assert!(rows.next().is_none());
+}
+
+
+#[test]
+fn test_predicates() {
+    let dir = test_dir("predicates");
+    let mut likes = open::<LikedSongs>(&dir);
+    let now = chrono::Utc::now();
+    let like = |id: i32, song: &str, user_id: i32, days_ago: i64| LikedSongs::new(
+        Int::new(id),
+        Str::new(song.into()),
+        Int::new(user_id),
+        Timestamp::new(now - chrono::Duration::days(days_ago)),
+    );
+    likes.insert_many([
+        like(1, "Underdog", 3, 1),
+        like(2, "Club foot", 4, 2),
+        like(3, "Fire", 3, 30),
+        like(4, "Empire", 5, 1),
+        like(5, "100% proof", 4, 6),
+        like(6, "Clubbed_to_death", 2, 3),
+    ]).unwrap();
+    let ids = |rows: Vec<LikedSongs>| rows.iter().map(|row| row.id.get()).collect::<Vec<_>>();
+
+    // Liked songs created in the last week by user 3 or 4
+    let week_ago = now - chrono::Duration::weeks(1);
+    let recent = LikedSongs::query().created_at().ge(week_ago).user_id().is_in([3, 4]);
+    assert_eq!(ids(likes.execute(recent).unwrap()), vec![1, 2, 5]);
+
+    let user = |id: i32| DType::Int(Int::new(id));
+    let song = |name: &str| DType::Str(Str::new(name.into()));
+    let cases = vec![
+        (Filter::Ne("user_id".into(), user(3)), vec![2, 4, 5, 6]),
+        (Filter::Between("user_id".into(), user(3), user(4)), vec![1, 2, 3, 5]),
+        (Filter::Or(vec![Filter::Eq("user_id".into(), user(2)), Filter::Eq("user_id".into(), user(5))]), vec![4, 6]),
+        (Filter::Or(vec![Filter::Eq("user_id".into(), user(2)), Filter::Eq("song".into(), song("Fire"))]), vec![3, 6]),
+        (Filter::Not(Box::new(Filter::In("user_id".into(), vec![user(3), user(4)]))), vec![4, 6]),
+        (Filter::And(vec![Filter::Lt("user_id".into(), user(5)), Filter::Not(Box::new(Filter::Ge("user_id".into(), user(3))))]), vec![6]),
+        (Filter::IsNull("song".into()), vec![]),
+        (Filter::Like("song".into(), "Club%".into()), vec![2, 6]),
+        (Filter::Like("song".into(), "%e".into()), vec![3, 4]),
+        (Filter::Like("song".into(), "_ire".into()), vec![3]),
+        (Filter::Like("song".into(), "%\\%%".into()), vec![5]),
+        (Filter::Like("song".into(), "%\\_to\\_%".into()), vec![6]),
+        (Filter::Like("song".into(), "%o%o%".into()), vec![2, 5]),
+        (Filter::Like("song".into(), "Fire_".into()), vec![]),
+    ];
+    for (filter, expected) in cases {
+        assert_eq!(ids(likes.select_where(&filter).unwrap()), expected, "{:?}", filter);
+    }
+
+    let query = LikedSongs::query().song().like("%d%").user_id().ne(3).id().between(2, 6);
+    assert_eq!(ids(likes.execute(query).unwrap()), vec![6]);
+
+    // Indexed lookups return rows in the order a scan does, also once a patch moved a row
+    likes.patch(2, LikedSongsPatch { song: Some(Str::new("Club foot (live)".into())), ..Default::default() }).unwrap();
+    let scanned = ids(likes.select_where(&Filter::In("song".into(), vec![song("Underdog"), song("Club foot (live)"), song("100% proof")])).unwrap());
+    assert_eq!(scanned, vec![1, 5, 2]);
+    let indexed = Filter::In("user_id".into(), vec![user(4), user(3)]);
+    assert_eq!(ids(likes.select_where(&indexed).unwrap()), vec![1, 3, 5, 2]);
+    let indexed = Filter::Or(vec![Filter::Eq("user_id".into(), user(4)), Filter::Eq("user_id".into(), user(2))]);
+    assert_eq!(ids(likes.select_where(&indexed).unwrap()), vec![5, 6, 2]);
+
+    let error = likes.select_where(&Filter::Or(vec![Filter::IsNull("genre".into())])).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::NotFound));
+}
+
+
+#[test]
+fn test_order_limit_offset() {
+    let dir = test_dir("order_limit_offset");
+    let mut likes = open::<LikedSongs>(&dir);
+    let now = chrono::Utc::now();
+    // Ids, users and ages are shuffled so that no two orders agree
+    likes.insert_many((1..=30).map(|id| LikedSongs::new(
+        Int::new(id),
+        Str::new(format!("song {}", id)),
+        Int::new(id * 7 % 5),
+        Timestamp::new(now - chrono::Duration::minutes((id * 13 % 31) as i64)),
+    ))).unwrap();
+    let ids = |rows: Vec<LikedSongs>| rows.iter().map(|row| row.id.get()).collect::<Vec<_>>();
+    let age = |id: i32| id * 13 % 31;
+
+    // The most recent likes, kept in a bounded heap
+    let mut by_age: Vec<i32> = (1..=30).collect();
+    by_age.sort_by_key(|id| age(*id));
+    let recent = LikedSongs::query().order_by(LikedSongsColumn::CreatedAt, Order::Desc).limit(5);
+    assert_eq!(ids(likes.execute(recent).unwrap()), by_age[..5]);
+    let page = LikedSongs::query().order_by(LikedSongsColumn::CreatedAt, Order::Desc).offset(5).limit(5);
+    assert_eq!(ids(likes.execute(page).unwrap()), by_age[5..10]);
+    let everything = LikedSongs::query().order_by(LikedSongsColumn::CreatedAt, Order::Asc).offset(27);
+    assert_eq!(ids(likes.execute(everything).unwrap()), by_age[..3].iter().rev().copied().collect::<Vec<_>>());
+
+    // Ordered by an index, ties keeping the stored order
+    let mut by_user: Vec<i32> = (1..=30).collect();
+    by_user.sort_by_key(|id| id * 7 % 5);
+    let query = LikedSongs::query().order_by(LikedSongsColumn::UserId, Order::Asc).limit(8);
+    assert_eq!(ids(likes.execute(query).unwrap()), by_user[..8]);
+    let query = LikedSongs::query().id().gt(20).order_by(LikedSongsColumn::Id, Order::Desc).offset(2).limit(3);
+    assert_eq!(ids(likes.execute(query).unwrap()), vec![28, 27, 26]);
+
+    // Several columns
+    let mut by_user_then_age: Vec<i32> = (1..=30).collect();
+    by_user_then_age.sort_by_key(|id| (std::cmp::Reverse(id * 7 % 5), age(*id)));
+    let query = LikedSongs::query()
+        .order_by(LikedSongsColumn::UserId, Order::Desc)
+        .order_by(LikedSongsColumn::CreatedAt, Order::Desc);
+    assert_eq!(ids(likes.execute(query).unwrap()), by_user_then_age);
+    let query = LikedSongs::query()
+        .user_id().eq(3)
+        .order_by(LikedSongsColumn::CreatedAt, Order::Asc)
+        .limit(2);
+    let expected: Vec<i32> = by_age.iter().rev().copied().filter(|id| id * 7 % 5 == 3).take(2).collect();
+    assert_eq!(ids(likes.execute(query).unwrap()), expected);
+
+    // A patch moves the row to the end of the file, and so to the end of its ties, whichever
+    // way the rows are sorted
+    likes.patch(4, LikedSongsPatch { song: Some(Str::new("song 4 (live)".into())), ..Default::default() }).unwrap();
+    let mut stored: Vec<i32> = (1..=30).filter(|id| *id != 4).collect();
+    stored.push(4);
+    for order in [Order::Asc, Order::Desc] {
+        let mut expected = stored.clone();
+        match order {
+            Order::Asc => expected.sort_by_key(|id| id * 7 % 5),
+            Order::Desc => expected.sort_by_key(|id| std::cmp::Reverse(id * 7 % 5)),
+        }
+        let indexed = LikedSongs::query().order_by(LikedSongsColumn::UserId, order);
+        // Sorting twice by the same column produces the same order without using the index
+        let sorted = LikedSongs::query().order_by(LikedSongsColumn::UserId, order).order_by(LikedSongsColumn::UserId, order);
+        assert_eq!(ids(likes.execute(indexed).unwrap()), expected);
+        assert_eq!(ids(likes.execute(sorted).unwrap()), expected);
+    }
+    let user_3 = ids(likes.execute(LikedSongs::query().user_id().eq(3)).unwrap());
+    assert_eq!(user_3, stored.iter().copied().filter(|id| id * 7 % 5 == 3).collect::<Vec<_>>());
+
+    // Without ordering rows come in the order they're stored
+    assert_eq!(ids(likes.execute(LikedSongs::query().offset(10).limit(3)).unwrap()), vec![12, 13, 14]);
+    assert_eq!(ids(likes.execute(LikedSongs::query().offset(29)).unwrap()), vec![4]);
+    assert!(likes.execute(LikedSongs::query().limit(0)).unwrap().is_empty());
+
+    let select = Select { order_by: vec![("rating".into(), Order::Asc)], ..Select::default() };
+    assert!(matches!(likes.select_by(&select).unwrap_err().kind, ErrorKind::NotFound));
+}
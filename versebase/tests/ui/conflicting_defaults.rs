@@ -0,0 +1,18 @@
+use versebase::datatypes::{DateTime, Int};
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Playlists {
+    pub id: Int,
+    #[versebase(auto_now_add, auto_now)]
+    pub created_at: DateTime,
+}
+
+#[derive(TableSchema)]
+pub struct Songs {
+    pub id: Int,
+    #[versebase(default)]
+    pub plays: Int,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use versebase::datatypes::Int;
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct LikedSongs {
+    #[versebase(primary_key)]
+    pub song_id: Int,
+    #[versebase(primary_key)]
+    pub user_id: Int,
+}
+
+fn main() {}
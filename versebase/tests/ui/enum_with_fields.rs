@@ -0,0 +1,14 @@
+use versebase_derive::VersebaseEnum;
+
+#[derive(VersebaseEnum)]
+pub enum Language {
+    En,
+    Other(String),
+}
+
+#[derive(VersebaseEnum)]
+pub struct Genre {
+    pub name: String,
+}
+
+fn main() {}
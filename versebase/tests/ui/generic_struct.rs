@@ -0,0 +1,10 @@
+use versebase::datatypes::Int;
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Songs<T> {
+    pub id: Int,
+    pub name: T,
+}
+
+fn main() {}
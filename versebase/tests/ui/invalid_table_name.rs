@@ -0,0 +1,16 @@
+use versebase::datatypes::Int;
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+#[versebase(table = "liked-songs")]
+pub struct LikedSongs {
+    pub id: Int,
+}
+
+#[derive(TableSchema)]
+#[versebase(table = "type")]
+pub struct Types {
+    pub id: Int,
+}
+
+fn main() {}
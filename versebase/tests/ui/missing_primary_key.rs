@@ -0,0 +1,10 @@
+use versebase::datatypes::{Int, Str};
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Songs {
+    pub song_id: Int,
+    pub name: Str,
+}
+
+fn main() {}
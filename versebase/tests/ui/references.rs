@@ -0,0 +1,24 @@
+use versebase::datatypes::{Int, Str};
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Artists {
+    pub id: Int,
+    pub name: Str,
+}
+
+#[derive(TableSchema)]
+pub struct Songs {
+    pub id: Int,
+    #[versebase(references = Artists)]
+    pub artist: Int,
+}
+
+#[derive(TableSchema)]
+pub struct Albums {
+    pub id: Int,
+    #[versebase(references = "artists")]
+    pub artist_id: Int,
+}
+
+fn main() {}
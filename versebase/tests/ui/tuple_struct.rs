@@ -0,0 +1,7 @@
+use versebase::datatypes::{Int, Str};
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Songs(Int, Str);
+
+fn main() {}
@@ -0,0 +1,17 @@
+use versebase::datatypes::{Int, Str};
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Songs {
+    pub id: Int,
+    #[versebase(indexed)]
+    pub name: Str,
+}
+
+#[derive(TableSchema)]
+#[versebase(name = "artists")]
+pub struct Artists {
+    pub id: Int,
+}
+
+fn main() {}
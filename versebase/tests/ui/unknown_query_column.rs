@@ -0,0 +1,14 @@
+use versebase::datatypes::{Int, Str};
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Songs {
+    pub id: Int,
+    pub name: Str,
+    pub artist_id: Int,
+}
+
+fn main() {
+    Songs::query().artist().eq(2);
+    Songs::query().id().starts_with("1");
+}
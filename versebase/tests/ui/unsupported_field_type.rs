@@ -0,0 +1,17 @@
+use versebase::datatypes::{Int, Str};
+use versebase_derive::TableSchema;
+
+#[derive(TableSchema)]
+pub struct Songs {
+    pub id: Int,
+    pub name: Str,
+    pub plays: u32,
+}
+
+#[derive(TableSchema)]
+pub struct Artists {
+    pub id: Int,
+    pub name: &'static str,
+}
+
+fn main() {}
@@ -1,97 +1,447 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use std::any::{Any, TypeId};
-use quote::{quote, format_ident, quote_spanned, IdentFragment, ToTokens};
-use quote::__private::ext::RepAsIteratorExt;
-use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Fields, LitStr, Token, Ident};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DataEnum, DataStruct, DeriveInput, Error, Fields};
 
 
-#[proc_macro_derive(TableSchema)]
+#[proc_macro_derive(TableSchema, attributes(versebase))]
 pub fn table_schema_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as DeriveInput);
 
     impl_table_schema(&ast)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
 
-fn impl_table_schema(ast: &syn::DeriveInput) -> TokenStream {
+#[proc_macro_derive(VersebaseEnum)]
+pub fn versebase_enum_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    impl_versebase_enum(&ast)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+
+fn impl_table_schema(ast: &DeriveInput) -> syn::Result<TokenStream2> {
     let name = &ast.ident;
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(&ast.generics, "TableSchema can't be derived for generic structs"));
+    }
     let fields = match &ast.data {
         Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => &fields.named,
-        _ => panic!("expected a struct with named fields"),
+        Data::Struct(DataStruct { fields: Fields::Unnamed(fields), .. }) => return Err(Error::new_spanned(
+            fields,
+            "TableSchema can't be derived for tuple structs, columns need names",
+        )),
+        Data::Struct(DataStruct { struct_token, .. }) => return Err(Error::new_spanned(
+            struct_token,
+            "TableSchema can't be derived for unit structs, expected a struct with named fields",
+        )),
+        Data::Enum(data) => return Err(Error::new_spanned(
+            data.enum_token,
+            "TableSchema can only be derived for structs; use VersebaseEnum for enum columns",
+        )),
+        Data::Union(data) => return Err(Error::new_spanned(
+            data.union_token,
+            "TableSchema can only be derived for structs",
+        )),
     };
-    // TODO: ensure only Int, Str, DateTime passed
     for field in fields {
-        match &field.ty {
-            syn::Type::Verbatim(_) => (),
-            syn::Type::Path(_) => (),
-            e => panic!("expected raw type, got {}", quote!(#e).to_string())
-        };
+        check_column_type(&field.ty)?;
     }
 
-    // TODO: verify "id" column exists
     let field_name: Vec<syn::Ident> = fields
         .iter()
-        .map(|field| (&field.ident).clone().unwrap())
+        .map(|field| field.ident.clone().unwrap())
         .collect()
         ;
-    // TODO: verify "id" column exists
-    // let field_name2 = vec!["versebase::datatypes::DType::Int".to_token_stream()];
-    // println!("{:?}", field_name2);
 
     let field_datatype: Vec<syn::Type> = fields
         .iter()
-        .map(|field|  (&field.ty).clone())
+        .map(|field| field.ty.clone())
         .collect()
         ;
 
+    let mut table_name = to_snake_case(&name.to_string());
+    let mut struct_validator = Vec::<syn::Path>::new();
+    for item in versebase_attributes(&ast.attrs)? {
+        match item.name.to_string().as_str() {
+            "table" => {
+                table_name = item.str_value()?;
+                // Also names the reverse accessors of `references`, so it must be an identifier
+                let is_ident = !table_name.starts_with("r#")
+                    && syn::parse_str::<syn::Ident>(&table_name).is_ok();
+                if !is_ident {
+                    return Err(Error::new_spanned(
+                        &item.value,
+                        format!("`{}` is not a valid table name, expected an identifier such as `liked_songs`", table_name),
+                    ));
+                }
+            },
+            "validate" => struct_validator.push(item.path_value("path::to_fn")?.clone()),
+            _ => return Err(Error::new_spanned(
+                &item.name,
+                "unknown struct attribute, expected `table = \"...\"` or `validate = path::to_fn`",
+            )),
+        }
+    }
+
+    let mut primary_key: Option<&syn::Field> = None;
+    let mut index_field = Vec::<syn::Ident>::new();
+    let mut index_unique = Vec::<bool>::new();
+    let mut references = Vec::<(&syn::Field, syn::Path)>::new();
+    let mut field_indexed = Vec::<bool>::new();
+    let mut field_unique = Vec::<bool>::new();
+    let mut field_validator = Vec::<(syn::Ident, syn::Path)>::new();
+    let mut field_default = Vec::<Option<FieldDefault>>::new();
+    for field in fields {
+        let (mut index, mut unique) = (false, false);
+        let mut default: Option<(FieldDefault, syn::Ident)> = None;
+        for item in versebase_attributes(&field.attrs)? {
+            match item.name.to_string().as_str() {
+                "primary_key" => {
+                    item.flag()?;
+                    if primary_key.is_some() {
+                        return Err(Error::new_spanned(
+                            &item.name,
+                            "only one field can be marked with `#[versebase(primary_key)]`",
+                        ));
+                    }
+                    primary_key = Some(field);
+                }
+                "index" => index = item.flag().map(|()| true)?,
+                "unique" => unique = item.flag().map(|()| true)?,
+                "references" => {
+                    references.push((field, item.path_value("TypeName")?.clone()));
+                    index = true;
+                }
+                "validate" => field_validator.push((
+                    field.ident.clone().unwrap(),
+                    item.path_value("path::to_fn")?.clone(),
+                )),
+                "default" | "auto_now_add" | "auto_now" => {
+                    if let Some((_, previous)) = &default {
+                        return Err(Error::new_spanned(
+                            &item.name,
+                            format!("`{}` conflicts with `{}`, a field can have only one default", item.name, previous),
+                        ));
+                    }
+                    let value = match item.name.to_string().as_str() {
+                        "default" => FieldDefault::Value(item.expr_value("value")?.clone()),
+                        "auto_now_add" => item.flag().map(|()| FieldDefault::AutoNowAdd)?,
+                        _ => item.flag().map(|()| FieldDefault::AutoNow)?,
+                    };
+                    default = Some((value, item.name.clone()));
+                }
+                _ => return Err(Error::new_spanned(
+                    &item.name,
+                    "unknown field attribute, expected one of `primary_key`, `index`, `unique`, `references`, `validate`, \
+                     `default`, `auto_now_add`, `auto_now`",
+                )),
+            }
+        }
+        if index || unique {
+            index_field.push(field.ident.clone().unwrap());
+            index_unique.push(unique);
+        }
+        field_indexed.push(index || unique);
+        field_unique.push(unique);
+        field_default.push(default.map(|(value, _)| value));
+    }
+    let primary_key = primary_key
+        .or_else(|| fields.iter().find(|field| field.ident.as_ref().unwrap() == "id"))
+        .ok_or_else(|| Error::new_spanned(
+            name,
+            "missing primary key, add an `id` field or mark one with `#[versebase(primary_key)]`",
+        ))?;
+    let primary_key_type = &primary_key.ty;
+    let primary_key = primary_key.ident.clone().unwrap();
+    let field_primary_key: Vec<bool> = field_name.iter().map(|field| *field == primary_key).collect();
+    let field_ordinal = 0..field_name.len();
+
+    // Point type errors at the offending field rather than at the derive
+    let column_type_check = field_datatype.iter().map(|ty| quote_spanned! {ty.span()=>
+        assert_column::<_, #ty>();
+    });
+    let primary_key_type_check = quote_spanned! {primary_key_type.span()=>
+        assert_id::<#primary_key_type>();
+    };
+    let validator_calls = field_validator.iter().map(|(field, validator)| quote_spanned! {validator.span()=>
+        #validator(&self.#field)
+            .map_err(|message| versebase::error::Error::validation(std::stringify!(#field), message))?;
+    });
+    let struct_validator_calls = struct_validator.iter().map(|validator| quote_spanned! {validator.span()=>
+        #validator(self)?;
+    });
+    let with_defaults = impl_with_defaults(&ast.vis, fields, &field_default);
+    let fill_auto = impl_fill_auto(fields, &field_default);
+    let relations = references
+        .iter()
+        .map(|(field, target)| impl_relation(name, &table_name, field, target, &references))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let name_str = name.to_string();
+    let vis = &ast.vis;
+    let column_enum = format_ident!("{}Column", name);
+    let query = format_ident!("{}Query", name);
+    let column_variant: Vec<syn::Ident> = field_name
+        .iter()
+        .map(|field| format_ident!("{}", to_camel_case(&field.to_string()), span = field.span()))
+        .collect()
+        ;
+    let column_doc = format!("Columns of `{}`.", name);
+    let query_doc = format!("Query on `{}` rows, see `{}::query()`.", name, name);
+    let patch = format_ident!("{}Patch", name);
+    let patch_doc = format!("Partial update of a `{}` row, see `Table::patch`; `None` fields are left as they are.", name);
+
     let gen = quote! {
 
+        const _: fn() = || {
+            fn assert_column<V, T: versebase::datatypes::DataType<V>>()
+                where versebase::datatypes::DType: From<T> {}
+            fn assert_id<T: versebase::datatypes::DataType<i32>>() {}
+            #( #column_type_check )*
+            #primary_key_type_check
+        };
+
+        #( #relations )*
+
         impl #name {
             fn new(#( #field_name: #field_datatype ),*) -> Self {
                 Self { #( #field_name ),* }
             }
+
+            #with_defaults
+
+            /// Starts a typed query, run it with `Table::execute`.
+            #vis fn query() -> #query {
+                #query { select: std::default::Default::default() }
+            }
+        }
+
+        #[doc = #column_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #column_enum {
+            #( #column_variant ),*
+        }
+
+        impl #column_enum {
+            pub const ALL: &'static [Self] = &[ #( Self::#column_variant ),* ];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #( Self::#column_variant => std::stringify!(#field_name) ),*
+                }
+            }
+        }
+
+        impl std::fmt::Display for #column_enum {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl From<#column_enum> for versebase::query::Column {
+            fn from(column: #column_enum) -> Self {
+                versebase::query::Column::Field(column.name().to_string())
+            }
+        }
+
+        #[doc = #query_doc]
+        #[derive(Debug, Clone, PartialEq)]
+        #vis struct #query {
+            select: versebase::query::Select,
+        }
+
+        impl #query {
+            #(
+                pub fn #field_name(self) -> versebase::query::ColumnFilter<Self, #field_datatype> {
+                    versebase::query::ColumnFilter::new(self, #column_enum::#column_variant.into())
+                }
+            )*
+        }
+
+        #[doc = #patch_doc]
+        #[derive(Clone, Default)]
+        #vis struct #patch {
+            #( pub #field_name: std::option::Option<#field_datatype> ),*
+        }
+
+        impl versebase::table::SchemaPatch for #patch {
+            type Schema = #name;
+
+            fn columns(&self) -> std::vec::Vec<&'static str> {
+                let mut columns = std::vec::Vec::new();
+                #(
+                    if self.#field_name.is_some() {
+                        columns.push(std::stringify!(#field_name));
+                    }
+                )*
+                columns
+            }
+
+            fn apply(self, row: &mut #name) {
+                #(
+                    if let Some(value) = self.#field_name {
+                        row.#field_name = value;
+                    }
+                )*
+            }
         }
 
-        impl TableSchema for #name {
+        impl versebase::query::QueryBuilder for #query {
+            type Schema = #name;
+            type Column = #column_enum;
+
+            fn select_mut(&mut self) -> &mut versebase::query::Select {
+                &mut self.select
+            }
+
+            fn into_select(self) -> versebase::query::Select {
+                self.select
+            }
+        }
+
+        impl versebase::table::TableSchema for #name {
 
             fn fields() -> std::vec::Vec<String> {
                 [ #( std::stringify!(#field_name).to_string() ),*].to_vec()
             }
 
-            fn print_info() {
-                #(
-                    println!(
-                        "field {:?} of type {:?}",
-                        std::stringify!(#field_name),
-                        std::stringify!(#field_datatype)
-                    )
-                );*
+            fn schema() -> versebase::schema::SchemaInfo {
+                versebase::schema::SchemaInfo {
+                    table: #table_name,
+                    columns: std::vec![
+                        #(
+                            versebase::schema::ColumnInfo {
+                                name: std::stringify!(#field_name),
+                                ordinal: #field_ordinal,
+                                kind: <#field_datatype as versebase::datatypes::DataType<_>>::kind(),
+                                nullable: <#field_datatype as versebase::datatypes::DataType<_>>::nullable(),
+                                primary_key: #field_primary_key,
+                                indexed: #field_indexed,
+                                unique: #field_unique,
+                            }
+                        ),*
+                    ],
+                }
+            }
+
+            fn table_name() -> &'static str {
+                #table_name
             }
 
-            fn from_(raw: std::vec::Vec<(String, Box<[u8]>)>) -> Self {
+            fn primary_key() -> &'static str {
+                std::stringify!(#primary_key)
+            }
+
+            fn indexes() -> std::vec::Vec<versebase::index::IndexSpec> {
+                std::vec![
+                    #(
+                        versebase::index::IndexSpec {
+                            field: std::stringify!(#index_field),
+                            unique: #index_unique,
+                        }
+                    ),*
+                ]
+            }
+
+            fn from_(
+                raw: std::vec::Vec<(String, Box<[u8]>)>
+            ) -> Result<Self, versebase::error::Error> {
                 let map: std::collections::HashMap<String, Box<[u8]>> = raw.into_iter().collect();
-                Self {
+                Ok(Self {
+                    #(
+                        #field_name: match map.get(std::stringify!(#field_name)) {
+                            Some(value) => <#field_datatype as versebase::datatypes::DataType<_>>::from_(value)
+                                .map_err(|e| versebase::error::Error {
+                                    kind: e.kind,
+                                    message: format!(
+                                        "column \"{}\": {}", std::stringify!(#field_name), e.message
+                                    ),
+                                })?,
+                            None => return Err(versebase::error::Error {
+                                kind: versebase::error::ErrorKind::Decode,
+                                message: format!("column \"{}\" is missing", std::stringify!(#field_name)),
+                            }),
+                        }
+                    ),*
+                })
+            }
+
+            fn from_strings(
+                values: std::collections::HashMap<String, String>
+            ) -> Result<Self, versebase::error::Error> {
+                Ok(Self {
                     #(
-                        #field_name: #field_datatype::from_(map[std::stringify!(#field_name)].deref())
+                        #field_name: match values.get(std::stringify!(#field_name)) {
+                            Some(text) => <#field_datatype as versebase::datatypes::DataType<_>>::parse(text)
+                                .map_err(|e| versebase::error::Error {
+                                    kind: e.kind,
+                                    message: format!(
+                                        "column \"{}\": {}", std::stringify!(#field_name), e.message
+                                    ),
+                                })?,
+                            None => return Err(versebase::error::Error {
+                                kind: versebase::error::ErrorKind::Parse,
+                                message: format!("column \"{}\" is missing", std::stringify!(#field_name)),
+                            }),
+                        }
                     ),*
+                })
+            }
+
+            fn parse_value(field: &str, text: &str) -> Result<versebase::datatypes::DType, versebase::error::Error> {
+                match field {
+                    #(
+                        std::stringify!(#field_name) => Ok(versebase::datatypes::DType::from(
+                            <#field_datatype as versebase::datatypes::DataType<_>>::parse(text)?
+                        ))
+                    ),*,
+                    _ => Err(versebase::error::Error {
+                        kind: versebase::error::ErrorKind::NotFound,
+                        message: format!("unknown column \"{}\"", field),
+                    }),
                 }
             }
 
+            fn validate(&self) -> Result<(), versebase::error::Error> {
+                #(
+                    <#field_datatype as versebase::datatypes::DataType<_>>::validate(&self.#field_name)
+                        .map_err(|e| versebase::error::Error {
+                            kind: e.kind,
+                            message: format!("column \"{}\": {}", std::stringify!(#field_name), e.message),
+                        })?;
+                )*
+                #( #validator_calls )*
+                #( #struct_validator_calls )*
+                Ok(())
+            }
+
             fn get(&self, field: String) -> Option<versebase::datatypes::DType> {
                 match field.as_str() {
                     #(
                         std::stringify!(#field_name) => Some(
-                            versebase::datatypes::DType::#field_datatype(self.#field_name.clone())
+                            versebase::datatypes::DType::from(self.#field_name.clone())
                         )
                     ),*,
                     _ => None,
                 }
             }
 
+            #fill_auto
+
             fn get_id(&self) -> i32 {
-                self.id.get()
+                versebase::datatypes::DataType::get(&self.#primary_key)
+            }
+
+            fn set_id(&mut self, id: i32) {
+                self.#primary_key = versebase::datatypes::DataType::new(id);
             }
 
             fn to_map(&self) -> std::collections::HashMap<String, versebase::datatypes::DType> {
@@ -99,7 +449,7 @@ fn impl_table_schema(ast: &syn::DeriveInput) -> TokenStream {
                     #(
                         (
                             String::from(std::stringify!(#field_name)),
-                            versebase::datatypes::DType::#field_datatype(self.#field_name.clone())
+                            versebase::datatypes::DType::from(self.#field_name.clone())
                         )
                     ),*
                 ])
@@ -110,7 +460,7 @@ fn impl_table_schema(ast: &syn::DeriveInput) -> TokenStream {
                 #(
                     serialized.push((
                         String::from(std::stringify!(#field_name)),
-                        self.#field_name.serialize()
+                        <#field_datatype as versebase::datatypes::DataType<_>>::serialize(&self.#field_name)
                     ));
                 );*
 
@@ -133,7 +483,7 @@ fn impl_table_schema(ast: &syn::DeriveInput) -> TokenStream {
                         format!(
                             "{}: {:?}",
                             std::stringify!(#field_name),
-                            self.#field_name.get()
+                            <#field_datatype as versebase::datatypes::DataType<_>>::get(&self.#field_name)
                         )
                     ),*
                 ];
@@ -143,7 +493,422 @@ fn impl_table_schema(ast: &syn::DeriveInput) -> TokenStream {
 
         }
 
+        versebase::__serde_impls! {
+            impl versebase::serde::Serialize for #name {
+                fn serialize<S: versebase::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    #[derive(versebase::serde::Serialize)]
+                    #[serde(crate = "versebase::serde", rename = #name_str)]
+                    struct Row<'a> {
+                        #( #field_name: &'a #field_datatype ),*
+                    }
+
+                    versebase::serde::Serialize::serialize(&Row { #( #field_name: &self.#field_name ),* }, serializer)
+                }
+            }
+
+            impl<'de> versebase::serde::Deserialize<'de> for #name {
+                fn deserialize<D: versebase::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    #[derive(versebase::serde::Deserialize)]
+                    #[serde(crate = "versebase::serde", rename = #name_str, deny_unknown_fields)]
+                    struct Row {
+                        #( #field_name: #field_datatype ),*
+                    }
+
+                    let row: Row = versebase::serde::Deserialize::deserialize(deserializer)?;
+                    let row = Self { #( #field_name: row.#field_name ),* };
+                    versebase::table::TableSchema::validate(&row)
+                        .map_err(|e| <D::Error as versebase::serde::de::Error>::custom(e.message))?;
+                    Ok(row)
+                }
+            }
+        }
+
     };
-    eprintln!("{}", gen.to_string());
-    gen.into()
-}
\ No newline at end of file
+    Ok(gen)
+}
+
+
+/// Value a field gets when it's left out of `with_defaults`.
+enum FieldDefault {
+    /// `#[versebase(default = value)]`, converted with `DataType::new`.
+    Value(syn::Expr),
+    /// `#[versebase(auto_now_add)]`, also set by `Table::create`.
+    AutoNowAdd,
+    /// `#[versebase(auto_now)]`, also set on every write.
+    AutoNow,
+}
+
+/// `with_defaults` constructor taking only the fields without a default; nothing if every
+/// field is required.
+fn impl_with_defaults(
+    vis: &syn::Visibility,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::Token![,]>,
+    field_default: &[Option<FieldDefault>],
+) -> TokenStream2 {
+    if field_default.iter().all(Option::is_none) {
+        return TokenStream2::new();
+    }
+    let mut argument = Vec::new();
+    let mut value = Vec::new();
+    for (field, default) in fields.iter().zip(field_default) {
+        let (name, ty) = (field.ident.as_ref().unwrap(), &field.ty);
+        value.push(match default {
+            None => {
+                argument.push(quote! { #name: #ty });
+                quote! { #name }
+            }
+            Some(FieldDefault::Value(expr)) => quote_spanned! {expr.span()=>
+                #name: <#ty as versebase::datatypes::DataType<_>>::new(std::convert::Into::into(#expr))
+            },
+            Some(FieldDefault::AutoNowAdd | FieldDefault::AutoNow) => quote_spanned! {ty.span()=>
+                #name: <#ty as versebase::datatypes::AutoNow>::now()
+            },
+        });
+    }
+    quote! {
+        /// Creates a row, filling the fields that have a default.
+        #vis fn with_defaults(#( #argument ),*) -> Self {
+            Self { #( #value ),* }
+        }
+    }
+}
+
+/// `TableSchema::fill_auto` setting the `auto_now_add` and `auto_now` fields, if there are any.
+fn impl_fill_auto(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::Token![,]>,
+    field_default: &[Option<FieldDefault>],
+) -> TokenStream2 {
+    let mut on_insert = Vec::new();
+    let mut on_write = Vec::new();
+    for (field, default) in fields.iter().zip(field_default) {
+        let (name, ty) = (field.ident.as_ref().unwrap(), &field.ty);
+        let now = quote_spanned! {ty.span()=> <#ty as versebase::datatypes::AutoNow>::now() };
+        match default {
+            Some(FieldDefault::AutoNowAdd) => on_insert.push(quote! { self.#name = #now; }),
+            Some(FieldDefault::AutoNow) => on_write.push(quote! { self.#name = #now; }),
+            _ => {}
+        }
+    }
+    if on_insert.is_empty() && on_write.is_empty() {
+        return TokenStream2::new();
+    }
+    let insert = if on_insert.is_empty() { quote! { _insert } } else { quote! { insert } };
+    let on_insert = (!on_insert.is_empty()).then(|| quote! {
+        if insert {
+            #( #on_insert )*
+        }
+    });
+    quote! {
+        fn fill_auto(&mut self, #insert: bool) {
+            #on_insert
+            #( #on_write )*
+        }
+    }
+}
+
+
+/// Rejects types that can't be columns, suggesting a datatype for the common Rust ones.
+/// Other types are checked by the compiler against `DataType`.
+fn check_column_type(ty: &syn::Type) -> syn::Result<()> {
+    let path = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+        _ => return Err(Error::new_spanned(
+            ty,
+            "unsupported column type, expected a versebase datatype such as `Int` or `Str`",
+        )),
+    };
+    let suggestion = match path.get_ident().map(|ident| ident.to_string()).as_deref() {
+        Some("i32") => "Int",
+        Some("i64") => "BigInt",
+        Some("f64") => "Float",
+        Some("bool") => "Bool",
+        Some("String") => "Str",
+        Some("i8" | "i16" | "u8" | "u16") => "Int",
+        Some("u32" | "i128" | "u64" | "u128" | "isize" | "usize") => "BigInt` or `Decimal",
+        Some("f32") => "Float",
+        Some("char") => "Char<1>",
+        _ => return Ok(()),
+    };
+    Err(Error::new_spanned(
+        ty,
+        format!("unsupported column type `{}`, use `{}` instead", quote!(#ty), suggestion),
+    ))
+}
+
+
+/// Accessors for a `#[versebase(references = Target)]` field: `name.target()` fetching the
+/// referenced row, and `target.table_name()` fetching every row referring to it. The latter
+/// is named `table_name_by_target()` if the struct refers to `Target` more than once.
+fn impl_relation(
+    name: &syn::Ident,
+    table_name: &str,
+    field: &syn::Field,
+    target: &syn::Path,
+    references: &[(&syn::Field, syn::Path)],
+) -> syn::Result<TokenStream2> {
+    let field_name = field.ident.as_ref().unwrap();
+    let field_type = &field.ty;
+    let forward = match field_name.to_string().strip_suffix("_id") {
+        Some(forward) if !forward.is_empty() => syn::Ident::new(forward, field_name.span()),
+        _ => return Err(Error::new_spanned(
+            field_name,
+            "a field with `references` must be named `<relation>_id`, the accessor is named after `<relation>`",
+        )),
+    };
+    let same_target = references
+        .iter()
+        .filter(|(_, other)| quote!(#other).to_string() == quote!(#target).to_string())
+        .count();
+    let reverse = match same_target {
+        1 => syn::Ident::new(table_name, name.span()),
+        _ => syn::Ident::new(&format!("{}_by_{}", table_name, forward), field_name.span()),
+    };
+    let id_type_check = quote_spanned! {field_type.span()=>
+        assert_id::<#field_type>();
+    };
+
+    Ok(quote! {
+        const _: fn() = || {
+            fn assert_id<T: versebase::datatypes::DataType<i32>>() {}
+            #id_type_check
+        };
+
+        impl #name {
+            pub fn #forward<D: versebase::table::HasTable<#target>>(
+                &self,
+                db: &mut D,
+            ) -> Result<#target, versebase::error::Error> {
+                db.table().get(<#field_type as versebase::datatypes::DataType<i32>>::get(&self.#field_name))
+            }
+        }
+
+        impl #target {
+            pub fn #reverse<D: versebase::table::HasTable<#name>>(
+                &self,
+                db: &mut D,
+            ) -> Result<std::vec::Vec<#name>, versebase::error::Error> {
+                db.table().select_where(&versebase::query::Filter::Eq(
+                    versebase::query::Column::from(std::stringify!(#field_name)),
+                    versebase::datatypes::DType::Int(<versebase::datatypes::Int as versebase::datatypes::DataType<i32>>::new(
+                        versebase::table::TableSchema::get_id(self)
+                    )),
+                ))
+            }
+        }
+    })
+}
+
+
+/// `name` or `name = value` inside `#[versebase(...)]`.
+struct AttributeItem {
+    name: syn::Ident,
+    value: Option<syn::Expr>,
+}
+
+impl syn::parse::Parse for AttributeItem {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let value = match input.parse::<Option<syn::Token![=]>>()? {
+            Some(_) => Some(input.parse()?),
+            None => None,
+        };
+        Ok(Self { name, value })
+    }
+}
+
+impl AttributeItem {
+    fn flag(&self) -> syn::Result<()> {
+        match &self.value {
+            None => Ok(()),
+            Some(value) => Err(Error::new_spanned(value, format!("`{}` doesn't take a value", self.name))),
+        }
+    }
+
+    fn expr_value(&self, expected: &str) -> syn::Result<&syn::Expr> {
+        self.value
+            .as_ref()
+            .ok_or_else(|| Error::new_spanned(&self.name, format!("expected `{} = {}`", self.name, expected)))
+    }
+
+    fn str_value(&self) -> syn::Result<String> {
+        match &self.value {
+            Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(value), .. })) => Ok(value.value()),
+            _ => Err(Error::new_spanned(&self.name, format!("expected `{} = \"...\"`", self.name))),
+        }
+    }
+
+    /// `expected` describes the path in the error, e.g. `TypeName`.
+    fn path_value(&self, expected: &str) -> syn::Result<&syn::Path> {
+        match &self.value {
+            Some(syn::Expr::Path(syn::ExprPath { path, qself: None, .. })) => Ok(path),
+            _ => Err(Error::new_spanned(&self.name, format!("expected `{} = {}`", self.name, expected))),
+        }
+    }
+}
+
+
+/// Items of every `#[versebase(...)]` attribute.
+fn versebase_attributes(attrs: &[syn::Attribute]) -> syn::Result<Vec<AttributeItem>> {
+    let mut items = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("versebase")) {
+        items.extend(attr.parse_args_with(
+            syn::punctuated::Punctuated::<AttributeItem, syn::Token![,]>::parse_terminated
+        )?);
+    }
+    Ok(items)
+}
+
+
+/// `LikedSongs` -> `liked_songs`
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            snake_case.push('_');
+        }
+        snake_case.extend(c.to_lowercase());
+    }
+    snake_case
+}
+
+
+/// `artist_id` -> `ArtistId`
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+
+fn impl_versebase_enum(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &ast.ident;
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(&ast.generics, "VersebaseEnum can't be derived for generic enums"));
+    }
+    let variants = match &ast.data {
+        Data::Enum(DataEnum { variants, .. }) => variants,
+        Data::Struct(data) => return Err(Error::new_spanned(
+            data.struct_token,
+            "VersebaseEnum can only be derived for enums; use TableSchema for tables",
+        )),
+        Data::Union(data) => return Err(Error::new_spanned(data.union_token, "VersebaseEnum can only be derived for enums")),
+    };
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                &variant.fields,
+                format!("expected a fieldless enum, variant `{}` has fields", variant.ident),
+            ));
+        }
+    }
+
+    let variant: Vec<syn::Ident> = variants
+        .iter()
+        .map(|variant| variant.ident.clone())
+        .collect()
+        ;
+
+    let gen = quote! {
+
+        impl versebase::datatypes::EnumType for #name {
+            fn type_name() -> &'static str {
+                std::stringify!(#name)
+            }
+
+            fn from_discriminant(discriminant: i32) -> Option<Self> {
+                #(
+                    if discriminant == Self::#variant as i32 {
+                        return Some(Self::#variant);
+                    }
+                )*
+                None
+            }
+
+            fn discriminant(&self) -> i32 {
+                match self {
+                    #( Self::#variant => Self::#variant as i32 ),*
+                }
+            }
+
+            fn variant_name(&self) -> &'static str {
+                match self {
+                    #( Self::#variant => std::stringify!(#variant) ),*
+                }
+            }
+        }
+
+        impl versebase::datatypes::DataType<#name> for #name {
+            fn new(value: Self) -> Self {
+                value
+            }
+
+            fn from_(raw: &[u8]) -> Result<Self, versebase::error::Error> {
+                versebase::datatypes::deserialize_enum(raw)
+            }
+
+            fn deserialize(raw: &[u8]) -> Result<Self, versebase::error::Error> {
+                versebase::datatypes::deserialize_enum(raw)
+            }
+
+            fn get(&self) -> Self {
+                match self {
+                    #( Self::#variant => Self::#variant ),*
+                }
+            }
+
+            fn serialize(&self) -> Box<[u8]> {
+                versebase::datatypes::EnumType::discriminant(self).to_ne_bytes().into()
+            }
+
+            fn kind() -> versebase::datatypes::DataKind {
+                versebase::datatypes::DataKind::Enum(<Self as versebase::datatypes::EnumType>::type_name())
+            }
+
+            fn parse(text: &str) -> Result<Self, versebase::error::Error> {
+                match text {
+                    #( std::stringify!(#variant) => Ok(Self::#variant), )*
+                    _ => Err(versebase::error::Error {
+                        kind: versebase::error::ErrorKind::Parse,
+                        message: format!(
+                            "{:?} is not a valid {}, expected one of: {}",
+                            text,
+                            std::stringify!(#name),
+                            [#( std::stringify!(#variant) ),*].join(", ")
+                        ),
+                    }),
+                }
+            }
+
+            fn format(&self) -> String {
+                versebase::datatypes::EnumType::variant_name(self).to_string()
+            }
+        }
+
+        impl From<#name> for versebase::datatypes::DType {
+            fn from(value: #name) -> Self {
+                versebase::datatypes::DType::Enum(versebase::datatypes::EnumValue::new(&value))
+            }
+        }
+
+        versebase::__serde_impls! {
+            impl versebase::serde::Serialize for #name {
+                fn serialize<S: versebase::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(versebase::datatypes::EnumType::variant_name(self))
+                }
+            }
+
+            impl<'de> versebase::serde::Deserialize<'de> for #name {
+                fn deserialize<D: versebase::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let text: String = versebase::serde::Deserialize::deserialize(deserializer)?;
+                    <Self as versebase::datatypes::DataType<_>>::parse(&text)
+                        .map_err(|e| <D::Error as versebase::serde::de::Error>::custom(e.message))
+                }
+            }
+        }
+
+    };
+    Ok(gen)
+}